@@ -0,0 +1,75 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Generates one test per `input/examples/day<n>_<name>.txt` fixture, checking it against the
+/// answers in the `day<n>_<name>.answers` file next to it, and tells the crate which days are
+/// enabled in `AOC_ENABLED_DAYS`.
+fn main() {
+    let examples = Path::new(env!("CARGO_MANIFEST_DIR")).join("input/examples");
+    println!("cargo:rerun-if-changed={}", examples.display());
+
+    let mut fixtures: Vec<_> = fs::read_dir(&examples)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|e| e.path())
+                .filter(|p| p.extension().map(|e| e == "txt").unwrap_or_default())
+                // the empty placeholders of a freshly scaffolded day have nothing to check yet
+                .filter(|p| fs::metadata(p).map(|m| m.len() > 0).unwrap_or_default())
+                // and days left out by their feature have no solver to check
+                .filter(|p| day_enabled(p))
+                .collect()
+        })
+        .unwrap_or_default();
+    fixtures.sort();
+
+    let mut tests = String::new();
+    for fixture in fixtures {
+        let name = fixture.file_stem().unwrap().to_string_lossy();
+        let test_name: String = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        let answers = fixture.with_extension("answers");
+        let answers = if answers.exists() {
+            format!("Some(include_str!({:?}))", answers.display().to_string())
+        } else {
+            "None".to_string()
+        };
+
+        tests.push_str(&format!(
+            "#[test]\nfn {}() {{\n    check_example({:?}, include_str!({:?}), {});\n}}\n\n",
+            test_name,
+            name,
+            fixture.display().to_string(),
+            answers
+        ));
+    }
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("examples.rs");
+    fs::write(out, tests).unwrap();
+
+    // the days whose feature is enabled, for the registry to be checked against
+    let mut days: Vec<u8> = env::vars_os()
+        .filter_map(|(key, _)| {
+            key.to_str()?
+                .strip_prefix("CARGO_FEATURE_DAY")?
+                .parse()
+                .ok()
+        })
+        .collect();
+    days.sort_unstable();
+    let days: Vec<String> = days.iter().map(u8::to_string).collect();
+    println!("cargo:rustc-env=AOC_ENABLED_DAYS={}", days.join(","));
+}
+
+/// Whether the feature of the day a `day<n>_<name>.txt` fixture belongs to is enabled.
+fn day_enabled(fixture: &Path) -> bool {
+    let name = fixture.file_stem().unwrap_or_default().to_string_lossy();
+    match name.strip_prefix("day").and_then(|n| n.split('_').next()) {
+        Some(day) => env::var_os(format!("CARGO_FEATURE_DAY{}", day)).is_some(),
+        // a misnamed fixture fails its test
+        None => true,
+    }
+}
@@ -0,0 +1,11 @@
+#![no_main]
+use aoc2020::day7::{self, Bags};
+use aoc2020::parse::Parse;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(bags) = Bags::parse(input) {
+        day7::part1(&bags, &mut vec![]);
+        let _ = day7::part2(&bags, &mut vec![]);
+    }
+});
@@ -0,0 +1,11 @@
+#![no_main]
+use aoc2020::day6::{self, Groups};
+use aoc2020::parse::Parse;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(groups) = Groups::parse(input) {
+        day6::part1(&groups, &mut vec![]);
+        day6::part2(&groups, &mut vec![]);
+    }
+});
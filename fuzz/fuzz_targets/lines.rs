@@ -0,0 +1,11 @@
+#![no_main]
+use aoc2020::day3::{self, Lines};
+use aoc2020::parse::Parse;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(lines) = Lines::parse(input) {
+        day3::part1(&lines, &mut vec![]);
+        let _ = day3::part2(&lines, &mut vec![]);
+    }
+});
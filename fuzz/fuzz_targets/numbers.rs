@@ -0,0 +1,18 @@
+#![no_main]
+use aoc2020::day1::{self, Entry, Numbers};
+use aoc2020::parse::Parse;
+use libfuzzer_sys::fuzz_target;
+
+fn solve<T: Entry>(input: &str) {
+    if let Ok(numbers) = Numbers::<T>::parse(input) {
+        let _ = day1::one(&numbers, &mut vec![]);
+        let _ = day1::two(&numbers, &mut vec![]);
+    }
+}
+
+fuzz_target!(|input: &str| {
+    solve::<i32>(input);
+    solve::<i64>(input);
+    solve::<u64>(input);
+    solve::<i128>(input);
+});
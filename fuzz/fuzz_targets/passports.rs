@@ -0,0 +1,13 @@
+#![no_main]
+use aoc2020::day4::{self, PassportData, PassportDataStrict, Passports};
+use aoc2020::parse::Parse;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(passports) = Passports::<PassportData>::parse(input) {
+        day4::part1(&passports, &mut vec![]);
+    }
+    if let Ok(passports) = Passports::<PassportDataStrict>::parse(input) {
+        day4::part2(&passports, &mut vec![]);
+    }
+});
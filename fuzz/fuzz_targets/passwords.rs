@@ -0,0 +1,13 @@
+#![no_main]
+use aoc2020::day2::{self, PasswordDay1, PasswordDay2, Passwords};
+use aoc2020::parse::Parse;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(passwords) = Passwords::<PasswordDay1>::parse(input) {
+        day2::first_step(&passwords, &mut vec![]);
+    }
+    if let Ok(passwords) = Passwords::<PasswordDay2>::parse(input) {
+        day2::second_step(&passwords, &mut vec![]);
+    }
+});
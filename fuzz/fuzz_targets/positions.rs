@@ -0,0 +1,11 @@
+#![no_main]
+use aoc2020::day5::{self, Positions};
+use aoc2020::parse::Parse;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(positions) = Positions::parse(input) {
+        let _ = day5::part1(&positions, &mut vec![]);
+        let _ = day5::part2(&positions, &mut vec![]);
+    }
+});
@@ -0,0 +1,170 @@
+//! Allocation profiling. With the `alloc-profile` feature the binary installs
+//! [`CountingAllocator`] and every part is reported with the allocations it made.
+
+/// What a measured closure allocated on its thread.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AllocStats {
+    pub allocations: u64,
+    pub bytes: u64,
+    /// The highest amount of live memory reached, above what was live before.
+    pub peak: u64,
+}
+
+/// Runs `func`, returning its allocations if the [`CountingAllocator`] is installed.
+pub fn measure<R>(func: impl FnOnce() -> R) -> (Option<AllocStats>, R) {
+    #[cfg(feature = "alloc-profile")]
+    {
+        counting::measure(func)
+    }
+    #[cfg(not(feature = "alloc-profile"))]
+    {
+        (None, func())
+    }
+}
+
+#[cfg(feature = "alloc-profile")]
+pub use counting::CountingAllocator;
+
+#[cfg(feature = "alloc-profile")]
+mod counting {
+    use super::AllocStats;
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// Counts are kept per thread, so that parts solved concurrently don't mix up.
+    #[derive(Debug, Clone, Copy)]
+    struct Counters {
+        allocations: u64,
+        bytes: u64,
+        current: u64,
+        peak: u64,
+    }
+
+    thread_local! {
+        static COUNTERS: Cell<Counters> = const {
+            Cell::new(Counters {
+                allocations: 0,
+                bytes: 0,
+                current: 0,
+                peak: 0,
+            })
+        };
+    }
+
+    static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+    /// A [`System`] allocator that counts what it does.
+    pub struct CountingAllocator;
+
+    fn update(func: impl FnOnce(&mut Counters)) {
+        // the thread local may be gone while a thread is being torn down
+        let _ = COUNTERS.try_with(|c| {
+            let mut counters = c.get();
+            func(&mut counters);
+            c.set(counters);
+        });
+    }
+
+    fn allocated(size: usize) {
+        INSTALLED.store(true, Ordering::Relaxed);
+        update(|c| {
+            c.allocations += 1;
+            c.bytes += size as u64;
+            c.current += size as u64;
+            c.peak = c.peak.max(c.current);
+        });
+    }
+
+    fn freed(size: usize) {
+        update(|c| c.current = c.current.saturating_sub(size as u64));
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                allocated(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc_zeroed(layout);
+            if !ptr.is_null() {
+                allocated(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            freed(layout.size());
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new_ptr = System.realloc(ptr, layout, new_size);
+            if !new_ptr.is_null() {
+                freed(layout.size());
+                allocated(new_size);
+            }
+            new_ptr
+        }
+    }
+
+    pub fn measure<R>(func: impl FnOnce() -> R) -> (Option<AllocStats>, R) {
+        if !INSTALLED.load(Ordering::Relaxed) {
+            return (None, func());
+        }
+
+        let mut start = COUNTERS.with(Cell::get);
+        start.peak = start.current;
+        COUNTERS.with(|c| c.set(start));
+        let result = func();
+        let end = COUNTERS.with(Cell::get);
+
+        let stats = AllocStats {
+            allocations: end.allocations - start.allocations,
+            bytes: end.bytes - start.bytes,
+            peak: end.peak - start.current,
+        };
+        (Some(stats), result)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[global_allocator]
+        static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+        #[test]
+        fn measure_counts_allocations() {
+            let (stats, _) = measure(|| {
+                let first = vec![0u8; 1000];
+                drop(first);
+                let second = vec![0u8; 400];
+                second.len()
+            });
+            let stats = stats.unwrap();
+
+            assert_eq!(2, stats.allocations);
+            assert_eq!(1400, stats.bytes);
+            assert_eq!(1000, stats.peak);
+        }
+
+        #[test]
+        fn measure_counts_reallocations() {
+            let (stats, _) = measure(|| {
+                let mut v: Vec<u8> = Vec::with_capacity(10);
+                v.reserve_exact(100);
+                v
+            });
+            let stats = stats.unwrap();
+
+            assert_eq!(2, stats.allocations);
+            assert_eq!(110, stats.bytes);
+            assert_eq!(100, stats.peak);
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use crate::parse::Parse;
+use crate::{AocError, AocResult, Part};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// The known correct answers, keyed by day and part.
+///
+/// The file has one `day<n> part<n> <answer>` entry per line; empty lines and lines
+/// starting with `#` are ignored.
+#[derive(Debug, Default, PartialEq)]
+pub struct Answers(BTreeMap<(u8, Part), String>);
+
+#[derive(Debug, PartialEq)]
+pub enum Check {
+    Pass,
+    Fail {
+        expected: String,
+    },
+    /// There is no recorded answer to compare with.
+    Unknown,
+}
+
+impl Answers {
+    pub fn get(&self, day: u8, part: Part) -> Option<&str> {
+        self.0.get(&(day, part)).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, day: u8, part: Part, answer: impl ToString) {
+        self.0.insert((day, part), answer.to_string());
+    }
+
+    pub fn check(&self, day: u8, part: Part, answer: &str) -> Check {
+        match self.get(day, part) {
+            Some(expected) if expected == answer => Check::Pass,
+            Some(expected) => Check::Fail {
+                expected: expected.to_string(),
+            },
+            None => Check::Unknown,
+        }
+    }
+}
+
+impl Parse<'_> for Answers {
+    fn parse(input: &str) -> AocResult<Self> {
+        input.parse()
+    }
+}
+
+impl FromStr for Answers {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut answers = Answers::default();
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || AocError::InvalidAnswer {
+                line: i + 1,
+                text: line.to_string(),
+            };
+
+            let mut parts = line.splitn(3, ' ');
+            let day = parts
+                .next()
+                .and_then(|d| d.strip_prefix("day"))
+                .and_then(|d| d.parse().ok())
+                .ok_or_else(invalid)?;
+            let part = parts
+                .next()
+                .and_then(|p| p.strip_prefix("part"))
+                .and_then(|p| p.parse().ok())
+                .and_then(Part::from_number)
+                .ok_or_else(invalid)?;
+            let answer = parts
+                .next()
+                .map(str::trim)
+                .filter(|a| !a.is_empty())
+                .ok_or_else(invalid)?;
+
+            answers.insert(day, part, answer);
+        }
+        Ok(answers)
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for ((day, part), answer) in &self.0 {
+            writeln!(f, "day{} part{} {}", day, part.number(), answer)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_format() {
+        let s = "# comment
+
+day7 part2 14177
+day1 part1 964875
+";
+        let answers: Answers = s.parse().unwrap();
+
+        assert_eq!(Some("964875"), answers.get(1, Part::One));
+        assert_eq!(Some("14177"), answers.get(7, Part::Two));
+        assert_eq!("day1 part1 964875\nday7 part2 14177\n", answers.to_string());
+    }
+
+    #[test]
+    fn parse_invalid_entry() {
+        let err = "day1 part1 1\nday1 part3 2".parse::<Answers>().unwrap_err();
+
+        assert_eq!(
+            "answers file, line 2: invalid entry \"day1 part3 2\"",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn check_answers() {
+        let mut answers = Answers::default();
+        answers.insert(3, Part::One, 7);
+
+        assert_eq!(Check::Pass, answers.check(3, Part::One, "7"));
+        assert_eq!(
+            Check::Fail {
+                expected: "7".to_string()
+            },
+            answers.check(3, Part::One, "8")
+        );
+        assert_eq!(Check::Unknown, answers.check(3, Part::Two, "8"));
+    }
+}
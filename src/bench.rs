@@ -0,0 +1,152 @@
+use std::fmt;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// When to stop sampling.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Budget {
+    Iterations(usize),
+    Time(Duration),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bench {
+    pub warmup: usize,
+    pub budget: Budget,
+}
+
+impl Default for Bench {
+    fn default() -> Self {
+        Self {
+            warmup: 3,
+            budget: Budget::Time(Duration::from_secs(1)),
+        }
+    }
+}
+
+impl Bench {
+    /// Calls `func` `warmup` times, then samples it until the budget is spent.
+    /// At least one sample is always taken.
+    pub fn run<R>(&self, mut func: impl FnMut() -> R) -> Stats {
+        for _ in 0..self.warmup {
+            black_box(func());
+        }
+
+        let mut samples = vec![];
+        let start = Instant::now();
+        loop {
+            let sample = Instant::now();
+            black_box(func());
+            samples.push(sample.elapsed());
+
+            let done = match self.budget {
+                Budget::Iterations(n) => samples.len() >= n,
+                Budget::Time(t) => start.elapsed() >= t,
+            };
+            if done {
+                break;
+            }
+        }
+
+        Stats::from_samples(samples)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub samples: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    /// Panics if there are no samples.
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "at least one sample is needed");
+        samples.sort();
+
+        let n = samples.len();
+        let median = if n.is_multiple_of(2) {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2
+        } else {
+            samples[n / 2]
+        };
+        let mean = samples.iter().map(Duration::as_secs_f64).sum::<f64>() / n as f64;
+        let variance = samples
+            .iter()
+            .map(|s| (s.as_secs_f64() - mean).powi(2))
+            .sum::<f64>()
+            / n as f64;
+
+        Self {
+            samples: n,
+            min: samples[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "min {:?}, median {:?}, mean {:?}, stddev {:?} ({} runs)",
+            self.min, self.median, self.mean, self.stddev, self.samples
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn stats_of_odd_samples() {
+        let stats = Stats::from_samples(vec![ms(3), ms(1), ms(2)]);
+
+        assert_eq!(3, stats.samples);
+        assert_eq!(ms(1), stats.min);
+        assert_eq!(ms(2), stats.median);
+        assert_eq!(ms(2), stats.mean);
+    }
+
+    #[test]
+    fn stats_of_even_samples() {
+        let stats = Stats::from_samples(vec![ms(4), ms(2), ms(4), ms(2)]);
+
+        assert_eq!(ms(3), stats.median);
+        assert_eq!(ms(3), stats.mean);
+        assert_eq!(ms(1), stats.stddev);
+    }
+
+    #[test]
+    fn bench_runs_warmup_and_iterations() {
+        let mut calls = 0;
+        let bench = Bench {
+            warmup: 2,
+            budget: Budget::Iterations(5),
+        };
+        let stats = bench.run(|| calls += 1);
+
+        assert_eq!(7, calls);
+        assert_eq!(5, stats.samples);
+    }
+
+    #[test]
+    fn bench_takes_one_sample_with_no_time_left() {
+        let bench = Bench {
+            warmup: 0,
+            budget: Budget::Time(Duration::from_secs(0)),
+        };
+
+        assert_eq!(1, bench.run(|| ()).samples);
+    }
+}
@@ -0,0 +1,667 @@
+use aoc2020::answers::{Answers, Check};
+use aoc2020::bench::{Bench, Budget};
+use aoc2020::client::{self, Client};
+use aoc2020::gen::{self, Options, Rng};
+use aoc2020::output::{Format, Locale, Output, Record};
+use aoc2020::scaffold;
+use aoc2020::submit::{self, Guess, History, Verdict};
+use aoc2020::utils::*;
+use aoc2020::watch::{self, Watcher};
+use aoc2020::{find_day, suite, AocError, AocResult, Day, Part, DAYS};
+use async_std::task;
+use std::env;
+use std::io::{self, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::str::FromStr;
+use std::time::Duration;
+
+#[cfg(feature = "alloc-profile")]
+#[global_allocator]
+static ALLOCATOR: aoc2020::allocs::CountingAllocator = aoc2020::allocs::CountingAllocator;
+
+const USAGE: &str = "usage: aoc fetch (--day <n> | --all) [--year <year>] [--base-url <url>]
+       aoc new <day> [--year <year>]
+       aoc gen <day> [--size <n>] [--seed <n>] [--valid-ratio <0..1>]
+       aoc submit --day <n> --part <1|2> [--answer <answer> | --input <path|->] [--year <year>]
+           [--base-url <url>] [--cooldown <secs>]
+       aoc (--day <n> | --all) [--part <1|2>] [--input <path|->] [--year <year>]
+           [--format <pretty|plain|json|csv>] [--lang <it|en>]
+           [--bench [--warmup <n>] [--iterations <n> | --budget-ms <ms>]]
+           [--record | --check] [--answers <path>] [--watch | --explain]";
+
+/// How many entries `gen` puts in an input by default.
+const GEN_SIZE: usize = 1000;
+
+/// How often `--watch` looks at the files of the day.
+const WATCH_INTERVAL: Duration = Duration::from_millis(300);
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+enum Command {
+    #[default]
+    Run,
+    Fetch,
+    Submit,
+    New,
+    Gen,
+}
+
+#[derive(Debug, Default)]
+struct Args {
+    command: Command,
+    day: Option<u8>,
+    part: Option<u8>,
+    input: Option<String>,
+    year: Option<u16>,
+    all: bool,
+    bench: Option<Bench>,
+    format: Format,
+    lang: Option<Locale>,
+    record: bool,
+    check: bool,
+    answers: Option<String>,
+    base_url: Option<String>,
+    answer: Option<String>,
+    cooldown: Option<u64>,
+    watch: bool,
+    explain: bool,
+    size: Option<usize>,
+    seed: Option<u64>,
+    valid_ratio: Option<f64>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "fetch" if parsed.command == Command::Run => parsed.command = Command::Fetch,
+            "submit" if parsed.command == Command::Run => parsed.command = Command::Submit,
+            "new" if parsed.command == Command::Run => {
+                parsed.command = Command::New;
+                parsed.day = Some(parse_value(&arg, args.next())?);
+            }
+            "gen" if parsed.command == Command::Run => {
+                parsed.command = Command::Gen;
+                parsed.day = Some(parse_value(&arg, args.next())?);
+            }
+            "--day" | "-d" => parsed.day = Some(parse_value(&arg, args.next())?),
+            "--part" | "-p" => parsed.part = Some(parse_value(&arg, args.next())?),
+            "--input" | "-i" => parsed.input = Some(parse_value(&arg, args.next())?),
+            "--year" | "-y" => parsed.year = Some(parse_value(&arg, args.next())?),
+            "--all" | "-a" => parsed.all = true,
+            "--format" | "-f" => parsed.format = parse_value(&arg, args.next())?,
+            "--lang" => parsed.lang = Some(parse_value(&arg, args.next())?),
+            "--record" => parsed.record = true,
+            "--check" => parsed.check = true,
+            "--watch" | "-w" => parsed.watch = true,
+            "--explain" | "-e" => parsed.explain = true,
+            "--answers" => parsed.answers = Some(parse_value(&arg, args.next())?),
+            "--base-url" => parsed.base_url = Some(parse_value(&arg, args.next())?),
+            "--answer" => parsed.answer = Some(parse_value(&arg, args.next())?),
+            "--cooldown" => parsed.cooldown = Some(parse_value(&arg, args.next())?),
+            "--size" => parsed.size = Some(parse_value(&arg, args.next())?),
+            "--seed" => parsed.seed = Some(parse_value(&arg, args.next())?),
+            "--valid-ratio" => parsed.valid_ratio = Some(parse_value(&arg, args.next())?),
+            "--bench" | "-b" => {
+                parsed.bench.get_or_insert_with(Bench::default);
+            }
+            "--warmup" => {
+                parsed.bench.get_or_insert_with(Bench::default).warmup =
+                    parse_value(&arg, args.next())?
+            }
+            "--iterations" => {
+                parsed.bench.get_or_insert_with(Bench::default).budget =
+                    Budget::Iterations(parse_value(&arg, args.next())?)
+            }
+            "--budget-ms" => {
+                parsed.bench.get_or_insert_with(Bench::default).budget =
+                    Budget::Time(Duration::from_millis(parse_value(&arg, args.next())?))
+            }
+            _ => return Err(format!("unknown argument {}", arg)),
+        }
+    }
+
+    match (parsed.day, parsed.all) {
+        (None, false) => return Err("one of --day or --all is required".to_string()),
+        (Some(_), true) => return Err("--day and --all are mutually exclusive".to_string()),
+        (Some(day), _)
+            if matches!(parsed.command, Command::Fetch | Command::New)
+                && !(1..=25).contains(&day) =>
+        {
+            return Err(format!("there is no day {}", day))
+        }
+        (Some(day), _) if parsed.command == Command::New && find_day(day).is_some() => {
+            return Err(format!("day {} already exists", day))
+        }
+        (Some(day), _) if parsed.command == Command::Gen && gen::generator(day).is_none() => {
+            return Err(format!("there is no generator for day {}", day))
+        }
+        (Some(day), _)
+            if matches!(parsed.command, Command::Run | Command::Submit)
+                && parsed.answer.is_none()
+                && find_day(day).is_none() =>
+        {
+            return Err(format!("day {} is not solved yet", day))
+        }
+        _ => {}
+    }
+    if parsed.command == Command::Submit && (parsed.all || parsed.part.is_none()) {
+        return Err("submit needs a single --day and --part".to_string());
+    }
+    if parsed.command != Command::Submit && (parsed.answer.is_some() || parsed.cooldown.is_some()) {
+        return Err("--answer and --cooldown can only be used with submit".to_string());
+    }
+    if parsed.command != Command::Gen
+        && (parsed.size.is_some() || parsed.seed.is_some() || parsed.valid_ratio.is_some())
+    {
+        return Err("--size, --seed and --valid-ratio can only be used with gen".to_string());
+    }
+    if let Some(ratio) = parsed.valid_ratio {
+        if !parsed
+            .day
+            .is_some_and(|day| gen::VALID_RATIO_DAYS.contains(&day))
+        {
+            return Err(format!(
+                "--valid-ratio can only be used to generate days {:?}",
+                gen::VALID_RATIO_DAYS
+            ));
+        }
+        if !(0.0..=1.0).contains(&ratio) {
+            return Err(format!(
+                "--valid-ratio must be between 0 and 1, got {}",
+                ratio
+            ));
+        }
+    }
+    if parsed.answer.is_some() && parsed.input.is_some() {
+        return Err("--answer and --input are mutually exclusive".to_string());
+    }
+    if parsed.all && parsed.input.is_some() {
+        return Err("--input can only be used with --day".to_string());
+    }
+    if parsed.watch {
+        if parsed.command != Command::Run || parsed.all {
+            return Err("--watch can only be used to run a single --day".to_string());
+        }
+        if parsed.bench.is_some() || parsed.record || parsed.check {
+            return Err("--watch can't be combined with --bench, --record or --check".to_string());
+        }
+        if parsed.input.as_deref() == Some("-") {
+            return Err("--watch can't read the input from stdin".to_string());
+        }
+    }
+    if parsed.explain {
+        if parsed.command != Command::Run || parsed.all {
+            return Err("--explain can only be used to run a single --day".to_string());
+        }
+        if parsed.bench.is_some() || parsed.watch {
+            return Err("--explain can't be combined with --bench or --watch".to_string());
+        }
+        if parsed.format == Format::Csv {
+            return Err("--explain can't be used with the csv format".to_string());
+        }
+    }
+    if parsed.record && parsed.check {
+        return Err("--record and --check are mutually exclusive".to_string());
+    }
+    if let Some(lang) = parsed.lang {
+        match parsed.format {
+            Format::Pretty(_) => parsed.format = Format::Pretty(lang),
+            _ => return Err("--lang can only be used with the pretty format".to_string()),
+        }
+    }
+    if let Some(part) = parsed.part {
+        if Part::from_number(part).is_none() {
+            return Err(format!("part must be 1 or 2, got {}", part));
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn parse_value<T: FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("missing value for {}", flag))?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid value {} for {}", value, flag))
+}
+
+/// The state of one invocation of the runner, shared by every solved part.
+struct Session<W: Write> {
+    args: Args,
+    output: Output<W>,
+    answers: Answers,
+    failures: usize,
+    passed: usize,
+}
+
+impl<W: Write> Session<W> {
+    fn solve(&mut self, day: &Day, part: Part, content: &str) -> AocResult<()> {
+        if self.args.explain {
+            return self.explain(day, part, content);
+        }
+        let record = match &self.args.bench {
+            Some(bench) => {
+                let outcome = day.bench(content, part, bench)?;
+                if !self.args.check {
+                    self.output.bench_details(&outcome.parse, &outcome.solve)?;
+                }
+                Record {
+                    day: day.number,
+                    part,
+                    answer: outcome.answer,
+                    parse_time: outcome.parse.median,
+                    solve_time: outcome.solve.median,
+                    allocs: None,
+                }
+            }
+            None => Record::new(day.number, part, day.run(content, part)?),
+        };
+
+        self.report(&record)
+    }
+
+    /// Solves a part with the decisions behind its answer, or behind it having none.
+    fn explain(&mut self, day: &Day, part: Part, content: &str) -> AocResult<()> {
+        let explanation = day.explain(content, part)?;
+        let solved = match explanation.outcome {
+            Ok(outcome) => self.report(&Record::new(day.number, part, outcome)),
+            Err(err) => Err(err),
+        };
+        self.output
+            .explanation(day.number, part, &explanation.events)?;
+        solved
+    }
+
+    fn report(&mut self, record: &Record) -> AocResult<()> {
+        if self.args.check {
+            self.check(record);
+        } else {
+            self.output.record(record)?;
+        }
+        if self.args.record {
+            self.answers.insert(record.day, record.part, &record.answer);
+        }
+        Ok(())
+    }
+
+    fn check(&mut self, record: &Record) {
+        let title = format!("day {} part {}", record.day, record.part.number());
+        match self.answers.check(record.day, record.part, &record.answer) {
+            Check::Pass => {
+                self.passed += 1;
+                println!("{}: ok", title);
+            }
+            Check::Fail { expected } => {
+                self.failures += 1;
+                println!("{}: FAIL", title);
+                println!("  - {}", expected);
+                println!("  + {}", record.answer);
+            }
+            Check::Unknown => println!("{}: no recorded answer, got {}", title, record.answer),
+        }
+    }
+
+    fn run(&mut self, day: &Day) -> AocResult<()> {
+        let input = task::block_on(load(&self.source(day)))?;
+        for part in self.parts() {
+            self.solve(day, part, input.text())?;
+        }
+        Ok(())
+    }
+
+    /// Runs a day, then runs it again whenever its input or one of its example fixtures
+    /// changes, until interrupted.
+    fn watch(&mut self, day: &Day) -> AocResult<()> {
+        let examples = Path::new(env!("CARGO_MANIFEST_DIR")).join("input/examples");
+        let fixtures = watch::examples(&examples, day.number);
+        let mut watcher = Watcher::new(self.source(day).candidates().into_iter().chain(fixtures));
+        let mut previous: Vec<Record> = vec![];
+
+        loop {
+            // a part that fails keeps its last good run to compare with
+            for record in self.rerun(day, &previous)? {
+                previous.retain(|r| r.part != record.part);
+                previous.push(record);
+            }
+            eprintln!("watching {} files, ctrl-c to stop", watcher.len());
+            for path in watcher.wait(WATCH_INTERVAL) {
+                eprintln!("\nchanged {}", path.display());
+            }
+        }
+    }
+
+    /// One run of `--watch`: the parts on the input, compared with the `previous` run, then
+    /// the example fixtures. Failures are reported without stopping the watch.
+    fn rerun(&mut self, day: &Day, previous: &[Record]) -> AocResult<Vec<Record>> {
+        let mut records = vec![];
+        match task::block_on(load(&self.source(day))) {
+            Ok(input) => {
+                for part in self.parts() {
+                    match catch_panic(|| day.run(input.text(), part)) {
+                        Ok(outcome) => records.push(Record::new(day.number, part, outcome)),
+                        Err(err) => eprintln!("part {}: {}", part.number(), err),
+                    }
+                }
+            }
+            Err(err) => eprintln!("input: {}", err),
+        }
+
+        for record in &records {
+            self.output.record(record)?;
+            let before = previous.iter().find(|r| r.part == record.part);
+            if let Some(before) = before.filter(|b| b.answer != record.answer) {
+                eprintln!("  answer changed, was {}", before.answer);
+            }
+            eprintln!(
+                "  parse {}, solve {}",
+                watch::delta(before.map(|b| b.parse_time), record.parse_time),
+                watch::delta(before.map(|b| b.solve_time), record.solve_time)
+            );
+        }
+
+        let examples = Path::new(env!("CARGO_MANIFEST_DIR")).join("input/examples");
+        for fixture in watch::examples(&examples, day.number) {
+            if fixture.extension().map(|e| e != "txt").unwrap_or(true) {
+                continue;
+            }
+            let name = fixture.file_stem().unwrap_or_default().to_string_lossy();
+            match catch_panic(|| watch::check_example(day, &fixture)) {
+                Ok(checked) => {
+                    for (part, answer, check) in checked {
+                        let title = format!("example {} part {}", name, part.number());
+                        match check {
+                            Check::Pass => eprintln!("{}: ok", title),
+                            Check::Fail { expected } => {
+                                eprintln!("{}: FAIL, expected {}, got {}", title, expected, answer)
+                            }
+                            Check::Unknown => {}
+                        }
+                    }
+                }
+                Err(err) => eprintln!("example {}: {}", name, err),
+            }
+        }
+        Ok(records)
+    }
+
+    /// Runs every day concurrently, then reports the records in day order. In the pretty
+    /// format a summary table takes the place of the single results.
+    fn run_all(&mut self) -> AocResult<()> {
+        let report = suite::run_all(DAYS, &self.parts(), |day| self.source(day))?;
+        let table = matches!(self.output.format(), Format::Pretty(_)) && !self.args.check;
+
+        for record in report.records() {
+            if table {
+                self.answers.insert(record.day, record.part, &record.answer);
+            } else {
+                self.report(record)?;
+            }
+        }
+
+        if table {
+            println!("{}", report);
+        } else {
+            for day in report.days.iter() {
+                if let Err(err) = &day.result {
+                    eprintln!("day {} FAILED: {}", day.day, err);
+                }
+            }
+            eprintln!("total wall-clock {:.1?}", report.wall_clock);
+        }
+        self.failures += report.failures();
+        Ok(())
+    }
+
+    fn source(&self, day: &Day) -> InputSource {
+        match &self.args.input {
+            Some(input) => InputSource::from_arg(input),
+            None => InputSource::named(self.year(), &format!("day{}", day.number)),
+        }
+    }
+
+    fn parts(&self) -> Vec<Part> {
+        match self.args.part.and_then(Part::from_number) {
+            Some(part) => vec![part],
+            None => Part::BOTH.to_vec(),
+        }
+    }
+
+    fn year(&self) -> u16 {
+        self.args.year.unwrap_or(DEFAULT_YEAR)
+    }
+
+    /// The explicit `--answers` file, or the `answers.txt` of the year.
+    fn answers_path(&self) -> PathBuf {
+        match &self.args.answers {
+            Some(path) => path.into(),
+            None => self.year_file("answers"),
+        }
+    }
+
+    /// The first `<name>.txt` found among the inputs of the year, or the one next to where
+    /// inputs are downloaded if there is none yet.
+    fn year_file(&self, name: &str) -> PathBuf {
+        let source = InputSource::named(self.year(), name);
+        source
+            .candidates()
+            .into_iter()
+            .find(|p| p.exists())
+            .or_else(|| source.store_path())
+            .expect("a named input can always be stored")
+    }
+
+    fn load_answers(&mut self) -> AocResult<()> {
+        let path = self.answers_path();
+        if self.args.check || path.exists() {
+            self.answers = task::block_on(load(&InputSource::Path(path)))?.parse()?;
+        }
+        Ok(())
+    }
+
+    fn save_answers(&self) -> AocResult<()> {
+        let path = self.answers_path();
+        task::block_on(async_std::fs::write(&path, self.answers.to_string()))?;
+        eprintln!("answers recorded in {}", path.display());
+        Ok(())
+    }
+
+    /// Downloads the missing inputs of the selected days. No session is needed when none
+    /// is missing.
+    fn fetch(&self) -> AocResult<()> {
+        let days: Vec<u8> = match self.args.day {
+            Some(day) => vec![day],
+            None => DAYS.iter().map(|d| d.number).collect(),
+        };
+        let mut missing = vec![];
+        for day in days {
+            let source = InputSource::named(self.year(), &format!("day{}", day));
+            match client::cached(&source) {
+                Some(path) => eprintln!("day {}: cached in {}", day, path.display()),
+                None => missing.push((day, source)),
+            }
+        }
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let client = Client::from_env(self.args.base_url.as_deref())?;
+        for (day, source) in missing {
+            let fetched = client.fetch(self.year(), day, &source)?;
+            eprintln!("day {}: downloaded to {}", day, fetched.path.display());
+        }
+        Ok(())
+    }
+
+    /// Submits the answer given with `--answer`, or the one the runner prints for the part,
+    /// unless the guesses submitted so far already rule it out.
+    fn submit(&mut self) -> AocResult<()> {
+        let (number, part) = match (self.args.day, self.args.part.and_then(Part::from_number)) {
+            (Some(day), Some(part)) => (day, part),
+            _ => unreachable!("submit arguments are validated"),
+        };
+        let answer = match (&self.args.answer, find_day(number)) {
+            (Some(answer), _) => answer.clone(),
+            (None, Some(day)) => {
+                let input = task::block_on(load(&self.source(day)))?;
+                let record = Record::new(number, part, day.run(input.text(), part)?);
+                self.output.record(&record)?;
+                record.answer
+            }
+            (None, None) => unreachable!("submit arguments are validated"),
+        };
+
+        let path = self.year_file("submissions");
+        let mut history: History = match path.exists() {
+            true => task::block_on(load(&InputSource::Path(path.clone())))?.parse()?,
+            false => History::default(),
+        };
+        let cooldown = self
+            .args
+            .cooldown
+            .map(Duration::from_secs)
+            .unwrap_or(submit::DEFAULT_COOLDOWN);
+        history.judge(number, part, &answer, submit::now(), cooldown)?;
+
+        let client = Client::from_env(self.args.base_url.as_deref())?;
+        let verdict = client.submit(self.year(), number, part, &answer)?;
+        history.push(Guess {
+            day: number,
+            part,
+            at: submit::now(),
+            verdict,
+            answer: answer.clone(),
+        });
+        task::block_on(async {
+            if let Some(dir) = path.parent() {
+                async_std::fs::create_dir_all(dir).await?;
+            }
+            async_std::fs::write(&path, history.to_string()).await
+        })?;
+
+        let title = format!("day {} part {}", number, part.number());
+        match verdict {
+            Verdict::Right => {
+                eprintln!("{}: {} is right", title, answer);
+                self.load_answers()?;
+                self.answers.insert(number, part, &answer);
+                self.save_answers()?;
+            }
+            Verdict::Wrong => eprintln!("{}: {} is wrong", title, answer),
+            Verdict::TooHigh => eprintln!("{}: {} is too high", title, answer),
+            Verdict::TooLow => eprintln!("{}: {} is too low", title, answer),
+            Verdict::Wait(wait) => eprintln!("{}: too early, wait {}s", title, wait.as_secs()),
+            Verdict::NotJudged => eprintln!(
+                "{}: {} was not judged, the part is locked or already solved",
+                title, answer
+            ),
+        }
+        if verdict != Verdict::Right {
+            self.failures += 1;
+        }
+        Ok(())
+    }
+
+    /// Generates the files of a new day in the repository the runner was built from.
+    fn scaffold(&self) -> AocResult<()> {
+        let day = self.args.day.expect("new arguments are validated");
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        for path in scaffold::create(root, self.year(), day)? {
+            eprintln!("created {}", path.display());
+        }
+        eprintln!(
+            "registered day {} in {} and {}",
+            day,
+            root.join("src/lib.rs").display(),
+            root.join("Cargo.toml").display()
+        );
+        Ok(())
+    }
+
+    /// Prints a random input of the day, then its answers to stderr in the format of the
+    /// answers file, so that they can be redirected to one.
+    fn generate(&self) -> AocResult<()> {
+        let day = self.args.day.expect("gen arguments are validated");
+        let generate = gen::generator(day).expect("gen arguments are validated");
+        let options = Options {
+            size: self.args.size.unwrap_or(GEN_SIZE),
+            valid_ratio: self.args.valid_ratio.unwrap_or(gen::DEFAULT_VALID_RATIO),
+        };
+        let seed = self.args.seed.unwrap_or_else(submit::now);
+
+        let generated = generate(&mut Rng::new(seed), options);
+        io::stdout().write_all(generated.input.as_bytes())?;
+        let ratio = match gen::VALID_RATIO_DAYS.contains(&day) {
+            true => format!(", valid ratio {}", options.valid_ratio),
+            false => String::new(),
+        };
+        eprint!(
+            "# day {}, size {}, seed {}{}\n{}",
+            day, options.size, seed, ratio, generated.answers
+        );
+        Ok(())
+    }
+
+    fn execute(&mut self) -> AocResult<()> {
+        match self.args.command {
+            Command::Fetch => return self.fetch(),
+            Command::Submit => return self.submit(),
+            Command::New => return self.scaffold(),
+            Command::Gen => return self.generate(),
+            Command::Run => {}
+        }
+        if self.args.check || self.args.record {
+            self.load_answers()?;
+        }
+        match self.args.day.and_then(find_day) {
+            Some(day) if self.args.watch => self.watch(day)?,
+            Some(day) => self.run(day)?,
+            None if self.args.bench.is_some() => DAYS.iter().try_for_each(|day| self.run(day))?,
+            None => self.run_all()?,
+        }
+        if self.args.record {
+            self.save_answers()?;
+        }
+        if self.args.check {
+            println!("{} passed, {} failed", self.passed, self.failures);
+        }
+        Ok(())
+    }
+}
+
+/// Runs `func`, turning a panic into an error, so that `--watch` survives a broken solver.
+fn catch_panic<T>(func: impl FnOnce() -> AocResult<T>) -> AocResult<T> {
+    panic::catch_unwind(AssertUnwindSafe(func)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_default();
+        Err(AocError::Panic(message))
+    })
+}
+
+fn main() {
+    let args = parse_args(env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{}\n{}", err, USAGE);
+        process::exit(2);
+    });
+
+    let stdout = io::stdout();
+    let mut session = Session {
+        output: Output::new(args.format, stdout.lock()),
+        args,
+        answers: Answers::default(),
+        failures: 0,
+        passed: 0,
+    };
+
+    if let Err(err) = session.execute() {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+    if session.failures > 0 {
+        process::exit(1);
+    }
+}
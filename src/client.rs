@@ -0,0 +1,281 @@
+use crate::submit::Verdict;
+use crate::utils::InputSource;
+use crate::{AocError, AocResult, Part};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::time::Duration;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Overrides [`DEFAULT_BASE_URL`], e.g. to talk to a local stub server.
+pub const BASE_URL_ENV: &str = "AOC_BASE_URL";
+
+/// The value of the `session` cookie of a logged in user.
+pub const SESSION_ENV: &str = "AOC_SESSION";
+
+const USER_AGENT: &str = "github.com/matteosister/aoc2020";
+
+/// A client of the Advent of Code website, authenticated with a session token.
+pub struct Client {
+    base_url: String,
+    session: String,
+    agent: ureq::Agent,
+}
+
+/// Where an input has been stored, and whether it had to be downloaded.
+#[derive(Debug, PartialEq)]
+pub struct Fetched {
+    pub path: PathBuf,
+    pub downloaded: bool,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.trim().to_string(),
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(30))
+                .user_agent(USER_AGENT)
+                .build(),
+        }
+    }
+
+    /// Builds a client with the session token of [`SESSION_ENV`] or of the
+    /// [`session_file`], and the base url of [`BASE_URL_ENV`] unless one is given.
+    pub fn from_env(base_url: Option<&str>) -> AocResult<Self> {
+        let base_url = match base_url {
+            Some(url) => url.to_string(),
+            None => env::var(BASE_URL_ENV).unwrap_or_else(|_| DEFAULT_BASE_URL.to_string()),
+        };
+        Ok(Self::new(&base_url, &session()?))
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub fn input(&self, year: u16, day: u8) -> AocResult<String> {
+        let url = format!("{}/{}/day/{}/input", self.base_url, year, day);
+        let response = self
+            .agent
+            .get(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .call()
+            .map_err(|e| http_error(&url, e))?;
+        response.into_string().map_err(|e| AocError::Http {
+            url,
+            reason: e.to_string(),
+        })
+    }
+
+    /// Downloads the input of a day where `source` looks for it, unless one of the
+    /// files `source` tries already exists, see [`cached`].
+    ///
+    /// The input is written next to where it belongs and then renamed into place, so that
+    /// an interrupted download never leaves a truncated file to be taken for a cached one.
+    pub fn fetch(&self, year: u16, day: u8, source: &InputSource) -> AocResult<Fetched> {
+        if let Some(path) = cached(source) {
+            return Ok(Fetched {
+                path,
+                downloaded: false,
+            });
+        }
+
+        let path = source
+            .store_path()
+            .ok_or_else(|| AocError::InputNotFound { tried: vec![] })?;
+        let input = self.input(year, day)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        let partial = path.with_file_name(format!(".{}.{}.part", file_name, process::id()));
+        if let Err(err) = fs::write(&partial, input).and_then(|_| fs::rename(&partial, &path)) {
+            let _ = fs::remove_file(&partial);
+            return Err(err.into());
+        }
+
+        Ok(Fetched {
+            path,
+            downloaded: true,
+        })
+    }
+
+    /// Posts an answer and reads the verdict out of the page the server answers with.
+    pub fn submit(&self, year: u16, day: u8, part: Part, answer: &str) -> AocResult<Verdict> {
+        let url = format!("{}/{}/day/{}/answer", self.base_url, year, day);
+        let level = part.number().to_string();
+        let response = self
+            .agent
+            .post(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .send_form(&[("level", &level), ("answer", answer)])
+            .map_err(|e| http_error(&url, e))?;
+        let page = response.into_string().map_err(|e| AocError::Http {
+            url: url.clone(),
+            reason: e.to_string(),
+        })?;
+        Verdict::from_response(&page).ok_or_else(|| AocError::Http {
+            url,
+            reason: "unrecognized response".to_string(),
+        })
+    }
+}
+
+/// The first of the files `source` tries that exists, which needs no download.
+pub fn cached(source: &InputSource) -> Option<PathBuf> {
+    source.candidates().into_iter().find(|p| p.is_file())
+}
+
+fn http_error(url: &str, err: ureq::Error) -> AocError {
+    let reason = match err {
+        ureq::Error::Status(code, response) => {
+            format!("{} {}", code, response.status_text())
+        }
+        ureq::Error::Transport(transport) => transport.to_string(),
+    };
+    AocError::Http {
+        url: url.to_string(),
+        reason,
+    }
+}
+
+/// `$XDG_CONFIG_HOME/aoc/session`, or `~/.config/aoc/session`.
+pub fn session_file() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|config| config.join("aoc").join("session"))
+}
+
+fn session() -> AocResult<String> {
+    if let Ok(session) = env::var(SESSION_ENV) {
+        return Ok(session);
+    }
+    let file = session_file();
+    file.as_ref()
+        .and_then(|f| fs::read_to_string(f).ok())
+        .filter(|s| !s.trim().is_empty())
+        .ok_or(AocError::MissingSession(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_path;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+
+    /// A server answering `responses.len()` requests with the given status and body,
+    /// returning the requests it received.
+    fn stub_server(responses: Vec<(u16, &'static str)>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let mut requests = vec![];
+            for (status, body) in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut request = String::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(len) = line.to_lowercase().strip_prefix("content-length:") {
+                        content_length = len.trim().parse().unwrap();
+                    }
+                    request.push_str(&line);
+                    if line == "\r\n" {
+                        break;
+                    }
+                }
+                let mut payload = vec![0; content_length];
+                reader.read_exact(&mut payload).unwrap();
+                request.push_str(&String::from_utf8(payload).unwrap());
+                requests.push(request);
+
+                write!(
+                    reader.get_mut(),
+                    "HTTP/1.1 {} Stub\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+            requests
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn fetch_downloads_once() {
+        let (url, server) = stub_server(vec![(200, "1721\n979\n")]);
+        let path = temp_path("fetch");
+        let source = InputSource::Path(path.clone());
+        let client = Client::new(&url, "abc123\n");
+
+        let first = client.fetch(2020, 1, &source).unwrap();
+        let second = client.fetch(2020, 1, &source).unwrap();
+        let requests = server.join().unwrap();
+
+        assert!(first.downloaded);
+        assert!(!second.downloaded);
+        assert_eq!(path, second.path);
+        assert_eq!("1721\n979\n", fs::read_to_string(&path).unwrap());
+        // nothing is left of the partial download
+        assert_eq!(1, fs::read_dir(path.parent().unwrap()).unwrap().count());
+        assert_eq!(Some(path), cached(&source));
+        assert_eq!(1, requests.len());
+        assert!(requests[0].starts_with("GET /2020/day/1/input HTTP/1.1\r\n"));
+        assert!(requests[0].contains("\r\nCookie: session=abc123\r\n"));
+    }
+
+    #[test]
+    fn fetch_reports_http_errors() {
+        let (url, server) = stub_server(vec![(404, "not found")]);
+        let source = InputSource::Path(temp_path("fetch-404"));
+        let client = Client::new(&url, "abc123");
+
+        let err = client.fetch(2020, 1, &source).unwrap_err();
+        server.join().unwrap();
+
+        assert_eq!(None, cached(&source));
+        assert_eq!(
+            format!("request to {}/2020/day/1/input failed: 404 Stub", url),
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn submit_posts_the_answer() {
+        let (url, server) = stub_server(vec![
+            (
+                200,
+                "<p>That's not the right answer; your answer is too high.</p>",
+            ),
+            (200, "<p>Nothing to see here.</p>"),
+        ]);
+        let client = Client::new(&url, "abc123");
+
+        let verdict = client.submit(2020, 7, Part::Two, "14177").unwrap();
+        let err = client.submit(2020, 7, Part::Two, "14176").unwrap_err();
+        let requests = server.join().unwrap();
+
+        assert_eq!(Verdict::TooHigh, verdict);
+        assert_eq!(
+            format!(
+                "request to {}/2020/day/7/answer failed: unrecognized response",
+                url
+            ),
+            err.to_string()
+        );
+        assert!(requests[0].starts_with("POST /2020/day/7/answer HTTP/1.1\r\n"));
+        assert!(requests[0].contains("\r\nCookie: session=abc123\r\n"));
+        assert!(requests[0].ends_with("\r\n\r\nlevel=2&answer=14177"));
+    }
+}
@@ -1,81 +1,1221 @@
+use crate::parse::Parse;
+use crate::trace::{Event, Trace};
+use crate::{AocError, AocResult, Solution};
+#[cfg(feature = "io")]
+use async_std::{io::prelude::BufReadExt, stream::Stream};
+use std::cmp::Ordering;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{self, Debug, Display, Formatter};
+use std::io::{self, BufRead, BufReader, Read};
+use std::num::ParseIntError;
+use std::ops::RangeInclusive;
 use std::str::FromStr;
 
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+
+    type Input<'a> = Numbers;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn part1(input: &Self::Input<'_>, trace: &mut impl Trace) -> AocResult<Self::Part1> {
+        one(input, trace)
+    }
+
+    fn part2(input: &Self::Input<'_>, trace: &mut impl Trace) -> AocResult<Self::Part2> {
+        two(input, trace)
+    }
+}
+
+/// The integers [`Numbers`] can hold.
+///
+/// Sums of entries are taken in `i128`, which holds any sum of fewer than 2^62 entries of 64
+/// bits or less. Entries of `i128` have to be small enough for their sums to fit as well, or
+/// the searches, from [`find_k_sum`] to [`k_sums_between`], fail with an overflow error.
+pub trait Entry: Copy + Ord + Debug + Display + FromStr<Err = ParseIntError> + From<u16> {
+    fn wide(self) -> i128;
+
+    fn checked_mul(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! entry {
+    ($($int:ty),*) => {
+        $(impl Entry for $int {
+            fn wide(self) -> i128 {
+                self as i128
+            }
+
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$int>::checked_mul(self, other)
+            }
+        })*
+    };
+}
+
+entry!(i32, i64, u64, i128);
+
+/// The expense report, one entry per line. The puzzle's entries are 64 bits wide.
 #[derive(Debug)]
-pub struct Numbers(Vec<i32>);
+pub struct Numbers<T = i64>(Vec<T>);
 
-pub fn one(data: Numbers) -> i32 {
-    let permutations = compute_permutations(data.0);
-    let correct_permutation = permutations
-        .iter()
-        .find(|(first, second)| first + second == 2020)
-        .unwrap();
-    correct_permutation.0 * correct_permutation.1
+impl<T: Entry> Numbers<T> {
+    pub fn entries(&self) -> &[T] {
+        &self.0
+    }
+
+    /// The `k` entries whose sum is closest to `target`, see [`closest_k_sum`].
+    pub fn closest_sum(&self, target: T, k: usize) -> AocResult<Option<KSum<T>>> {
+        closest_k_sum(&self.0, target, k)
+    }
+
+    /// Every combination of `k` entries whose sum is in `range`, see [`k_sums_between`].
+    pub fn sums_between(&self, range: RangeInclusive<T>, k: usize) -> AocResult<Vec<KSum<T>>> {
+        k_sums_between(&self.0, range, k)
+    }
+}
+
+/// What the entries of the expense report have to sum to.
+pub const TARGET: u16 = 2020;
+
+/// The product of the two entries summing to 2020.
+pub fn one<T: Entry>(data: &Numbers<T>, trace: &mut impl Trace) -> AocResult<T> {
+    product_of_sum(data.entries(), T::from(TARGET), 2, trace)
+}
+
+pub fn two<T: Entry>(data: &Numbers<T>, trace: &mut impl Trace) -> AocResult<T> {
+    product_of_sum(data.entries(), T::from(TARGET), 3, trace)
+}
+
+/// The product of `k` entries summing to `target`. Fails when there are none, or when the
+/// product overflows.
+pub fn product_of_sum<T: Entry>(
+    numbers: &[T],
+    target: T,
+    k: usize,
+    trace: &mut impl Trace,
+) -> AocResult<T> {
+    let sum = match find_k_sum(numbers, target, k)? {
+        Some(sum) => sum,
+        None => {
+            trace.event(|| {
+                let what = match k {
+                    2 => "pairs".to_string(),
+                    3 => "triples".to_string(),
+                    k => format!("sets of {}", k),
+                };
+                let closest = closest_k_sum(numbers, target, k).ok().flatten();
+                let decision = match closest.as_ref().map(|closest| (closest, closest.sum())) {
+                    Some((closest, Ok(sum))) => {
+                        format!("none sums to {}, closest is {} = {}", target, closest, sum)
+                    }
+                    _ => format!("none sums to {}", target),
+                };
+                Event::new(what, decision)
+            });
+            return Err(AocError::no_answer(
+                Day1::DAY,
+                format!("no {} entries sum to {}", k, target),
+            ));
+        }
+    };
+    let product = sum.product();
+    trace.event(|| {
+        let product = match &product {
+            Ok(product) => product.to_string(),
+            Err(_) => "overflows".to_string(),
+        };
+        Event::new(&sum, format!("sums to {}, product {}", target, product))
+    });
+    product
+}
+
+/// `k` distinct entries of the input summing to a target, in input order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KSum<T = i64> {
+    pub indices: Vec<usize>,
+    pub values: Vec<T>,
+}
+
+impl<T: Entry> KSum<T> {
+    /// Built from positions in the entries sorted by [`sort_entries`].
+    fn at<'a>(sorted: &[(T, usize)], positions: impl Iterator<Item = &'a usize>) -> Self {
+        let mut entries: Vec<(usize, T)> =
+            positions.map(|at| (sorted[*at].1, sorted[*at].0)).collect();
+        entries.sort_unstable();
+        let (indices, values) = entries.into_iter().unzip();
+        KSum { indices, values }
+    }
+
+    /// What the entries add up to, an error when it overflows.
+    pub fn sum(&self) -> AocResult<i128> {
+        self.values
+            .iter()
+            .try_fold(0i128, |sum, value| sum.checked_add(value.wide()))
+            .ok_or_else(|| AocError::overflow(Day1::DAY, format!("the sum of {}", self)))
+    }
+
+    /// What the entries multiply to, an error when it overflows.
+    pub fn product(&self) -> AocResult<T> {
+        self.values
+            .iter()
+            .try_fold(T::from(1), |product, value| product.checked_mul(*value))
+            .ok_or_else(|| {
+                let values: Vec<String> = self.values.iter().map(T::to_string).collect();
+                AocError::overflow(Day1::DAY, format!("the product of {}", values.join(" * ")))
+            })
+    }
 }
 
-pub fn two(data: Numbers) -> i32 {
-    let permutations = compute_permutations_for_3(data.0);
-    let correct_permutation = permutations
+/// The values joined with `+`.
+impl<T: Entry> Display for KSum<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let values: Vec<String> = self.values.iter().map(T::to_string).collect();
+        f.write_str(&values.join(" + "))
+    }
+}
+
+/// Fails when sums of the entries, or their differences to a value `bound` away from zero,
+/// might not fit in an `i128`.
+fn check_width<T: Entry>(numbers: &[T], bound: u128) -> AocResult<()> {
+    let total = numbers
         .iter()
-        .find(|(first, second, third)| first + second + third == 2020)
-        .unwrap();
-    correct_permutation.0 * correct_permutation.1 * correct_permutation.2
+        .try_fold(bound, |total, n| total.checked_add(n.wide().unsigned_abs()));
+    match total.is_none_or(|total| total > i128::MAX as u128) {
+        true => Err(AocError::overflow(Day1::DAY, "summing the entries")),
+        false => Ok(()),
+    }
+}
+
+/// The entries with their indices, by value, once [`check_width`] passes.
+fn sort_entries<T: Entry>(numbers: &[T], bound: u128) -> AocResult<Vec<(T, usize)>> {
+    check_width(numbers, bound)?;
+    let mut sorted: Vec<(T, usize)> = numbers.iter().copied().zip(0..).collect();
+    sorted.sort_unstable();
+    Ok(sorted)
+}
+
+/// Finds `k` entries, each used once, that sum to `target`, or `None` if no `k` of them do.
+/// When several do, this is the first of [`k_sums`].
+pub fn find_k_sum<T: Entry>(numbers: &[T], target: T, k: usize) -> AocResult<Option<KSum<T>>> {
+    Ok(k_sums(numbers, target, k)?.next())
+}
+
+/// How many different combinations [`k_sums`] finds. This counts on a sorted copy of
+/// `numbers`, [`count_k_sums_in_place`] does without it.
+pub fn count_k_sums<T: Entry>(numbers: &[T], target: T, k: usize) -> AocResult<usize> {
+    count_k_sums_in_place(&mut numbers.to_vec(), target, k)
+}
+
+/// How many different combinations [`k_sums`] finds, sorting `numbers` in place and
+/// allocating nothing: the search goes over the values alone, one level of recursion per
+/// entry fixed before the last two.
+pub fn count_k_sums_in_place<T: Entry>(numbers: &mut [T], target: T, k: usize) -> AocResult<usize> {
+    check_width(numbers, target.wide().unsigned_abs())?;
+    numbers.sort_unstable();
+    Ok(count_sorted(numbers, target.wide(), k))
+}
+
+/// The combinations of `k` values of `sorted` summing to `target`, each value used at most
+/// as many times as it is there.
+fn count_sorted<T: Entry>(sorted: &[T], target: i128, k: usize) -> usize {
+    match k {
+        0 => (target == 0) as usize,
+        1 => sorted.binary_search_by(|n| n.wide().cmp(&target)).is_ok() as usize,
+        2 => {
+            let (mut count, mut low, mut high) = (0, 0, sorted.len().saturating_sub(1));
+            while low < high {
+                match (sorted[low].wide() + sorted[high].wide()).cmp(&target) {
+                    Ordering::Less => low += 1,
+                    Ordering::Greater => high -= 1,
+                    Ordering::Equal => {
+                        count += 1;
+                        let (first, last) = (sorted[low], sorted[high]);
+                        while low < high && sorted[low] == first {
+                            low += 1;
+                        }
+                        while low < high && sorted[high] == last {
+                            high -= 1;
+                        }
+                    }
+                }
+            }
+            count
+        }
+        k => (0..sorted.len())
+            // the same first value would count the same combinations again
+            .filter(|at| *at == 0 || sorted[at - 1] != sorted[*at])
+            .map(|at| count_sorted(&sorted[at + 1..], target - sorted[at].wide(), k - 1))
+            .sum(),
+    }
+}
+
+/// Every combination of `k` entries, each used once, that sums to `target`, found lazily.
+///
+/// Entries of the same value are interchangeable: each combination of values comes once,
+/// with the first indices holding them, and a value is used at most as many times as it is
+/// in `numbers`.
+///
+/// The entries are sorted once up front. Then for every way to fix all but two entries of a
+/// combination, the last two are looked for from both ends of what is left, which takes
+/// O(n^(k-1)) time for all the combinations when `k` is at least 2.
+pub fn k_sums<T: Entry>(numbers: &[T], target: T, k: usize) -> AocResult<KSums<T>> {
+    let mut sums = KSums {
+        sorted: sort_entries(numbers, target.wide().unsigned_abs())?,
+        target: target.wide(),
+        prefix: vec![],
+        pair: None,
+        rest: 0,
+        low: 0,
+        high: 0,
+        search: Search::Done,
+    };
+    sums.search = match k {
+        0 if target.wide() == 0 => Search::Once,
+        1 => {
+            let at = sums.sorted.partition_point(|(value, _)| *value < target);
+            match sums.sorted.get(at) {
+                Some((value, _)) if *value == target => {
+                    sums.prefix.push(at);
+                    Search::Once
+                }
+                _ => Search::Done,
+            }
+        }
+        k if k >= 2 => {
+            sums.prefix.resize(k - 2, 0);
+            match sums.reset(0) {
+                true => Search::Pairs,
+                false => Search::Done,
+            }
+        }
+        _ => Search::Done,
+    };
+    Ok(sums)
+}
+
+/// The combinations of [`k_sums`].
+#[derive(Debug)]
+pub struct KSums<T> {
+    /// The values with their indices, by value.
+    sorted: Vec<(T, usize)>,
+    target: i128,
+    /// The positions in `sorted` of all but the last two entries of the combination.
+    prefix: Vec<usize>,
+    /// The positions of the last two entries, found between `low` and `high`.
+    pair: Option<[usize; 2]>,
+    /// What the last two entries have to sum to.
+    rest: i128,
+    low: usize,
+    high: usize,
+    search: Search,
+}
+
+#[derive(Debug)]
+enum Search {
+    /// Nothing is left to find.
+    Done,
+    /// `prefix` holds the only combination there is.
+    Once,
+    /// Combinations end with a pair after `prefix`.
+    Pairs,
+}
+
+impl<T: Entry> KSums<T> {
+    /// Moves on to the next combination, false when there is none.
+    fn advance(&mut self) -> bool {
+        match self.search {
+            Search::Done => false,
+            Search::Once => {
+                self.search = Search::Done;
+                true
+            }
+            Search::Pairs => loop {
+                if let Some(pair) = self.next_pair() {
+                    self.pair = Some(pair);
+                    return true;
+                }
+                if !self.next_prefix() {
+                    self.search = Search::Done;
+                    return false;
+                }
+            },
+        }
+    }
+
+    /// The next two positions between `low` and `high` summing to `rest`.
+    fn next_pair(&mut self) -> Option<[usize; 2]> {
+        while self.low < self.high {
+            let (low, high) = (self.low, self.high);
+            let sum = self.sorted[low].0.wide() + self.sorted[high].0.wide();
+            match sum.cmp(&self.rest) {
+                Ordering::Less => self.low += 1,
+                Ordering::Greater => self.high -= 1,
+                Ordering::Equal => {
+                    // of equal values, the first ones are reported
+                    let mut first = high;
+                    while first > low + 1 && self.sorted[first - 1].0 == self.sorted[high].0 {
+                        first -= 1;
+                    }
+                    // the next pair differs on both ends
+                    while self.low < high && self.sorted[self.low].0 == self.sorted[low].0 {
+                        self.low += 1;
+                    }
+                    while self.low < self.high && self.sorted[self.high].0 == self.sorted[high].0 {
+                        self.high -= 1;
+                    }
+                    return Some([low, first]);
+                }
+            }
+        }
+        None
+    }
+
+    /// Moves the prefix to the next one with other values, false when there is none.
+    fn next_prefix(&mut self) -> bool {
+        for level in (0..self.prefix.len()).rev() {
+            let at = self.prefix[level];
+            // the same value here would find the same combinations again
+            let next =
+                (at + 1..self.sorted.len()).find(|next| self.sorted[*next].0 != self.sorted[at].0);
+            if let Some(next) = next {
+                self.prefix[level] = next;
+                if self.reset(level + 1) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Puts the first positions after `prefix[..level]` in the rest of the prefix, to look
+    /// for pairs after them. False when too few entries are left to make a combination.
+    fn reset(&mut self, level: usize) -> bool {
+        let mut next = match level {
+            0 => 0,
+            level => self.prefix[level - 1] + 1,
+        };
+        for at in &mut self.prefix[level..] {
+            *at = next;
+            next += 1;
+        }
+        if next + 2 > self.sorted.len() {
+            return false;
+        }
+        let fixed: i128 = self.prefix.iter().map(|at| self.sorted[*at].0.wide()).sum();
+        self.rest = self.target - fixed;
+        self.low = next;
+        self.high = self.sorted.len() - 1;
+        true
+    }
+}
+
+impl<T: Entry> Iterator for KSums<T> {
+    type Item = KSum<T>;
+
+    fn next(&mut self) -> Option<KSum<T>> {
+        if !self.advance() {
+            return None;
+        }
+        let positions = self.prefix.iter().chain(self.pair.iter().flatten());
+        Some(KSum::at(&self.sorted, positions))
+    }
+
+    fn count(mut self) -> usize {
+        let mut count = 0;
+        while self.advance() {
+            count += 1;
+        }
+        count
+    }
+}
+
+/// The `k` entries, each used once, whose sum is closest to `target`, the lower sum when two
+/// are as close, or `None` when there are fewer than `k` entries.
+///
+/// Like [`k_sums`], all but the last two entries are fixed in every way, then the last two
+/// are looked for from both ends of what is left, in O(n^(k-1)) time for `k` of at least 2.
+pub fn closest_k_sum<T: Entry>(numbers: &[T], target: T, k: usize) -> AocResult<Option<KSum<T>>> {
+    let sorted = sort_entries(numbers, target.wide().unsigned_abs())?;
+    if sorted.len() < k {
+        return Ok(None);
+    }
+    let mut closest = Closest {
+        sorted: &sorted,
+        target: target.wide(),
+        chosen: Vec::with_capacity(k),
+        best: None,
+    };
+    closest.search(0, k, 0);
+    Ok(closest
+        .best
+        .map(|(_, positions)| KSum::at(&sorted, positions.iter())))
+}
+
+/// The search for [`closest_k_sum`].
+struct Closest<'a, T> {
+    sorted: &'a [(T, usize)],
+    target: i128,
+    /// The positions in `sorted` fixed so far.
+    chosen: Vec<usize>,
+    /// The sum closest to the target yet, with its positions.
+    best: Option<(i128, Vec<usize>)>,
+}
+
+impl<T: Entry> Closest<'_, T> {
+    /// Looks for `k` more entries from position `from` on, the fixed ones summing to `fixed`.
+    fn search(&mut self, from: usize, k: usize, fixed: i128) {
+        let sorted = self.sorted;
+        let value = |at: usize| sorted[at].0.wide();
+        match k {
+            0 => self.consider(fixed, &[]),
+            1 => {
+                let rest = self.target - fixed;
+                let at = from + self.sorted[from..].partition_point(|(v, _)| v.wide() < rest);
+                if at > from {
+                    self.consider(fixed + value(at - 1), &[at - 1]);
+                }
+                if at < self.sorted.len() {
+                    self.consider(fixed + value(at), &[at]);
+                }
+            }
+            2 => {
+                let (mut low, mut high) = (from, self.sorted.len() - 1);
+                while low < high {
+                    let sum = fixed + value(low) + value(high);
+                    self.consider(sum, &[low, high]);
+                    match sum.cmp(&self.target) {
+                        Ordering::Less => low += 1,
+                        Ordering::Greater => high -= 1,
+                        Ordering::Equal => return,
+                    }
+                }
+            }
+            _ => {
+                for at in from..=self.sorted.len() - k {
+                    // the same value as the previous one finds nothing closer
+                    if at > from && self.sorted[at - 1].0 == self.sorted[at].0 {
+                        continue;
+                    }
+                    if self.is_exact() {
+                        return;
+                    }
+                    self.chosen.push(at);
+                    self.search(at + 1, k - 1, fixed + value(at));
+                    self.chosen.pop();
+                }
+            }
+        }
+    }
+
+    /// Keeps the fixed entries and those `last` ones when their `sum` is the closest yet.
+    fn consider(&mut self, sum: i128, last: &[usize]) {
+        let distance = |sum: i128| ((sum - self.target).abs(), sum);
+        if let Some((best, _)) = self.best {
+            if distance(best) <= distance(sum) {
+                return;
+            }
+        }
+        let positions = self.chosen.iter().chain(last).copied().collect();
+        self.best = Some((sum, positions));
+    }
+
+    fn is_exact(&self) -> bool {
+        matches!(self.best, Some((sum, _)) if sum == self.target)
+    }
+}
+
+/// Every combination of `k` entries, each used once, whose sum is in `range`, by their values
+/// in increasing order. As with [`k_sums`], each combination of values comes once.
+pub fn k_sums_between<T: Entry>(
+    numbers: &[T],
+    range: RangeInclusive<T>,
+    k: usize,
+) -> AocResult<Vec<KSum<T>>> {
+    let (low, high) = (range.start().wide(), range.end().wide());
+    let sorted = sort_entries(numbers, low.unsigned_abs().max(high.unsigned_abs()))?;
+    let mut found = vec![];
+    let mut chosen = Vec::with_capacity(k);
+    sums_between(&sorted, 0, k, low..=high, &mut chosen, &mut found);
+    Ok(found)
+}
+
+/// Pushes to `found` every combination of `k` entries from position `from` on whose sum is in
+/// `range`, after the `chosen` ones.
+fn sums_between<T: Entry>(
+    sorted: &[(T, usize)],
+    from: usize,
+    k: usize,
+    range: RangeInclusive<i128>,
+    chosen: &mut Vec<usize>,
+    found: &mut Vec<KSum<T>>,
+) {
+    let value = |at: usize| sorted[at].0.wide();
+    if k == 0 {
+        if range.contains(&0) {
+            found.push(KSum::at(sorted, chosen.iter()));
+        }
+        return;
+    }
+    if sorted.len() - from < k {
+        return;
+    }
+    // the largest sum of k - 1 entries, to skip what stays below the range
+    let largest: i128 = (sorted.len() + 1 - k..sorted.len()).map(value).sum();
+    for at in from..=sorted.len() - k {
+        if at > from && sorted[at - 1].0 == sorted[at].0 {
+            continue;
+        }
+        let smallest: i128 = (at..at + k).map(value).sum();
+        if smallest > *range.end() {
+            break;
+        }
+        if value(at) + largest < *range.start() {
+            continue;
+        }
+        chosen.push(at);
+        let rest = range.start() - value(at)..=range.end() - value(at);
+        sums_between(sorted, at + 1, k - 1, rest, chosen, found);
+        chosen.pop();
+    }
+}
+
+/// An incremental index of the entries seen so far, for the combinations of `k` of them
+/// summing to a target while the entries come one at a time.
+///
+/// Only the distinct values are kept, each with how many times it was seen and its first `k`
+/// indices, so inputs with few distinct values take little memory however long they are.
+#[derive(Debug)]
+pub struct KSumIndex<T> {
+    target: i128,
+    k: usize,
+    seen: HashMap<i128, Seen<T>>,
+    /// The index of the next entry.
+    entries: usize,
+}
+
+#[derive(Debug)]
+struct Seen<T> {
+    value: T,
+    count: usize,
+    /// The first indices of the value, at most `k`.
+    indices: Vec<usize>,
+}
+
+impl<T: Entry> KSumIndex<T> {
+    pub fn new(target: T, k: usize) -> Self {
+        Self {
+            target: target.wide(),
+            k,
+            seen: HashMap::new(),
+            entries: 0,
+        }
+    }
+
+    /// Takes the next entry and returns the combinations it completes, in no particular order.
+    ///
+    /// As with [`k_sums`], each combination of values comes once, with the first indices
+    /// holding them: when the last entry it needs is pushed. Finding them takes O(d^(k-2))
+    /// time for `d` distinct values seen and `k` of at least 2. No entry completes the empty
+    /// combination, for `k` of 0.
+    pub fn push(&mut self, value: T) -> Vec<KSum<T>> {
+        let index = self.entries;
+        self.entries += 1;
+        let key = value.wide();
+        let seen = self.seen.entry(key).or_insert_with(|| Seen {
+            value,
+            count: 0,
+            indices: vec![],
+        });
+        seen.count += 1;
+        if seen.indices.len() < self.k {
+            seen.indices.push(index);
+        }
+        let count = seen.count;
+
+        let mut found = vec![];
+        // a value seen more than k times completes nothing new
+        if (1..=self.k).contains(&count) {
+            let mut others = Vec::with_capacity(self.k - 1);
+            if let Some(rest) = self.target.checked_sub(key) {
+                self.complete(key, rest, self.k - 1, None, &mut others, &mut found);
+            }
+        }
+        found
+    }
+
+    /// Looks for `k` more values seen, none below `min`, summing to `rest` with the `others`
+    /// already picked, to go with the value `key` just pushed. A difference out of the range
+    /// of `i128` can't be made of values that fit in it.
+    fn complete(
+        &self,
+        key: i128,
+        rest: i128,
+        k: usize,
+        min: Option<i128>,
+        others: &mut Vec<i128>,
+        found: &mut Vec<KSum<T>>,
+    ) {
+        match k {
+            0 if rest == 0 => self.check(key, others, found),
+            0 => {}
+            1 => {
+                if min.is_none_or(|min| rest >= min) && self.seen.contains_key(&rest) {
+                    others.push(rest);
+                    self.check(key, others, found);
+                    others.pop();
+                }
+            }
+            _ => {
+                for other in self.seen.keys() {
+                    if min.is_some_and(|min| *other < min) {
+                        continue;
+                    }
+                    if let Some(rest) = rest.checked_sub(*other) {
+                        others.push(*other);
+                        self.complete(key, rest, k - 1, Some(*other), others, found);
+                        others.pop();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Adds the combination of the `others` and the value `key` just pushed to `found`, when
+    /// enough of every value was seen and it needs every entry of `key` seen so far.
+    fn check(&self, key: i128, others: &[i128], found: &mut Vec<KSum<T>>) {
+        let needed = |value: i128| others.iter().filter(|o| **o == value).count();
+        if needed(key) + 1 != self.seen[&key].count {
+            return;
+        }
+        let mut values = others.to_vec();
+        values.push(key);
+        values.sort_unstable();
+        values.dedup();
+
+        let mut entries = vec![];
+        for value in values {
+            let seen = &self.seen[&value];
+            let needed = needed(value) + usize::from(value == key);
+            if needed > seen.count {
+                return;
+            }
+            entries.extend(seen.indices[..needed].iter().map(|i| (*i, seen.value)));
+        }
+        entries.sort_unstable();
+        let (indices, values) = entries.into_iter().unzip();
+        found.push(KSum { indices, values });
+    }
+}
+
+/// The combinations of `k` entries summing to `target` in the lines of `reader`, each as soon
+/// as its last entry is read, see [`KSumIndex`]. Lines are read as [`Numbers`] parses them,
+/// and the first error, reading or parsing, ends the combinations.
+pub fn stream_k_sums<R: Read, T: Entry>(reader: R, target: T, k: usize) -> StreamedKSums<R, T> {
+    StreamedKSums {
+        lines: BufReader::new(reader).lines(),
+        feed: Feed::new(target, k),
+    }
+}
+
+/// The combinations of [`stream_k_sums`].
+#[derive(Debug)]
+pub struct StreamedKSums<R, T> {
+    lines: io::Lines<BufReader<R>>,
+    feed: Feed<T>,
+}
+
+impl<R: Read, T: Entry> Iterator for StreamedKSums<R, T> {
+    type Item = AocResult<KSum<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(sum) = self.feed.found.pop_front() {
+                return Some(sum);
+            }
+            if self.feed.done {
+                return None;
+            }
+            self.feed.take(self.lines.next());
+        }
+    }
+}
+
+/// [`stream_k_sums`] from an async reader.
+#[cfg(feature = "io")]
+pub fn stream_k_sums_async<R: async_std::io::Read + Unpin, T: Entry>(
+    reader: R,
+    target: T,
+    k: usize,
+) -> AsyncStreamedKSums<R, T> {
+    AsyncStreamedKSums {
+        lines: async_std::io::BufReader::new(reader).lines(),
+        feed: Feed::new(target, k),
+    }
+}
+
+/// The combinations of [`stream_k_sums_async`].
+#[cfg(feature = "io")]
+pub struct AsyncStreamedKSums<R, T> {
+    lines: async_std::io::Lines<async_std::io::BufReader<R>>,
+    feed: Feed<T>,
 }
 
-fn compute_permutations<T: Copy>(list: Vec<T>) -> Vec<(T, T)> {
-    let mut result = vec![];
-    for (i, first) in list.iter().enumerate() {
-        for second in list[i + 1..].iter() {
-            result.push((*first, *second))
+#[cfg(feature = "io")]
+impl<R: async_std::io::Read + Unpin, T: Entry + Unpin> Stream for AsyncStreamedKSums<R, T> {
+    type Item = AocResult<KSum<T>>;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        use std::task::Poll;
+
+        let this = self.get_mut();
+        loop {
+            if let Some(sum) = this.feed.found.pop_front() {
+                return Poll::Ready(Some(sum));
+            }
+            if this.feed.done {
+                return Poll::Ready(None);
+            }
+            match std::pin::Pin::new(&mut this.lines).poll_next(cx) {
+                Poll::Ready(line) => this.feed.take(line),
+                Poll::Pending => return Poll::Pending,
+            }
         }
     }
-    result
 }
 
-fn compute_permutations_for_3<T: Copy>(list: Vec<T>) -> Vec<(T, T, T)> {
-    let mut result = vec![];
-    for (i, first) in list.iter().enumerate() {
-        for second in list[i + 1..].iter() {
-            for third in list[i + 2..].iter() {
-                result.push((*first, *second, *third))
+/// What the lines read so far gave, sync or async.
+#[derive(Debug)]
+struct Feed<T> {
+    index: KSumIndex<T>,
+    /// The number of the last line read.
+    line: usize,
+    /// What is left to return.
+    found: VecDeque<AocResult<KSum<T>>>,
+    done: bool,
+}
+
+impl<T: Entry> Feed<T> {
+    fn new(target: T, k: usize) -> Self {
+        Self {
+            index: KSumIndex::new(target, k),
+            line: 0,
+            found: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /// Takes the next line, `None` at the end of the input.
+    fn take(&mut self, line: Option<io::Result<String>>) {
+        let entry = match line {
+            None => {
+                self.done = true;
+                return;
+            }
+            Some(line) => line.map_err(AocError::from).and_then(|line| {
+                self.line += 1;
+                parse_entry(self.line, &line).transpose()
+            }),
+        };
+        match entry {
+            Ok(Some(value)) => self
+                .found
+                .extend(self.index.push(value).into_iter().map(Ok)),
+            Ok(None) => {}
+            Err(e) => {
+                self.found.push_back(Err(e));
+                self.done = true;
             }
         }
     }
-    result
 }
 
-impl FromStr for Numbers {
-    type Err = ();
+/// The entry on the line numbered `number`, nothing when it is blank.
+fn parse_entry<T: Entry>(number: usize, line: &str) -> Option<AocResult<T>> {
+    let n = line.trim();
+    if n.is_empty() {
+        return None;
+    }
+    Some(
+        n.parse()
+            .map_err(|e| AocError::parse(Day1::DAY, number, n, e)),
+    )
+}
+
+/// One entry per line, signed or not as `T` allows, with blank lines and the whitespace around
+/// entries ignored.
+impl<T: Entry> FromStr for Numbers<T> {
+    type Err = AocError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let numbers = s.lines().map(|n| n.parse().unwrap()).collect();
+        let numbers = s
+            .lines()
+            .enumerate()
+            .filter_map(|(i, line)| parse_entry(i + 1, line))
+            .collect::<AocResult<_>>()?;
 
         Ok(Numbers(numbers))
     }
 }
 
+impl<T: Entry> Parse<'_> for Numbers<T> {
+    fn parse(input: &str) -> AocResult<Self> {
+        input.parse()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::trace::NoTrace;
 
     #[test]
-    fn compute_permutations_for_3_element_list() {
-        let list: Vec<i32> = vec![1, 2, 3];
-        assert_eq!(vec![(1, 2), (1, 3), (2, 3)], compute_permutations(list));
+    fn find_k_sum_for_3_element_list() {
+        let list = [3, 1, 2];
+        let sum = |indices: Vec<usize>, values: Vec<i32>| Some(KSum { indices, values });
+
+        assert_eq!(
+            sum(vec![1, 2], vec![1, 2]),
+            find_k_sum(&list, 3, 2).unwrap()
+        );
+        assert_eq!(
+            sum(vec![0, 1, 2], vec![3, 1, 2]),
+            find_k_sum(&list, 6, 3).unwrap()
+        );
+        assert_eq!(sum(vec![0], vec![3]), find_k_sum(&list, 3, 1).unwrap());
+        assert_eq!(sum(vec![], vec![]), find_k_sum(&list, 0, 0).unwrap());
+        assert_eq!(None, find_k_sum(&list, 6, 2).unwrap());
+        assert_eq!(None, find_k_sum(&list, 6, 4).unwrap());
     }
 
     #[test]
-    fn compute_permutations_for_empty_list() {
-        let list: Vec<i32> = vec![];
-        let result: Vec<(i32, i32)> = vec![];
+    fn find_k_sum_uses_every_entry_once() {
+        // the old scan for triples could pick 1010 twice, or the same entry three times
+        assert_eq!(None, find_k_sum(&[1010, 0, 5], 2020, 3).unwrap());
+        assert_eq!(None, find_k_sum(&[1010], 2020, 2).unwrap());
+        assert_eq!(None, find_k_sum(&[2, 2018, 673], 2019, 3).unwrap());
+        assert_eq!(
+            Some(vec![1, 2]),
+            find_k_sum(&[7, 1010, 1010], 2020, 2)
+                .unwrap()
+                .map(|s| s.indices)
+        );
+    }
 
-        assert_eq!(result, compute_permutations(list));
+    #[test]
+    fn find_k_sum_for_empty_list() {
+        assert_eq!(None, find_k_sum(&[], 2020, 2).unwrap());
+        assert_eq!(None, find_k_sum(&[], 2020, 3).unwrap());
     }
 
     #[test]
-    fn compute_permutations_for_single_element_list() {
-        let list: Vec<i32> = vec![1];
-        let result: Vec<(i32, i32)> = vec![];
+    fn find_k_sum_for_single_element_list() {
+        assert_eq!(None, find_k_sum(&[2020], 2020, 2).unwrap());
+        assert_eq!(None, find_k_sum(&[2020], 2020, 3).unwrap());
+    }
 
-        assert_eq!(result, compute_permutations(list));
+    #[test]
+    fn find_k_sum_without_overflow() {
+        let list = [i32::MAX, i32::MAX, i32::MIN, -3];
+        assert_eq!(
+            Some(vec![0, 1, 2, 3]),
+            find_k_sum(&list, i32::MAX - 4, 4)
+                .unwrap()
+                .map(|s| s.indices)
+        );
+    }
+
+    #[test]
+    fn k_sums_of_a_multiset() {
+        let list = [5, 1, 5, 4, 5, 6, 0, 10];
+        let values = |k| {
+            k_sums(&list, 10, k)
+                .unwrap()
+                .map(|s| s.values)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(vec![vec![10]], values(1));
+        assert_eq!(vec![vec![0, 10], vec![4, 6], vec![5, 5]], values(2));
+        assert_eq!(vec![vec![4, 6, 0], vec![5, 5, 0], vec![5, 1, 4]], values(3));
+        assert_eq!(vec![vec![5, 1, 4, 0]], values(4));
+        assert_eq!(Vec::<Vec<i32>>::new(), values(5));
+        assert_eq!(
+            Some(vec![0, 2]),
+            k_sums(&list, 10, 2).unwrap().nth(2).map(|s| s.indices)
+        );
+        assert_eq!(4, count_k_sums(&list, 15, 3).unwrap());
+        assert_eq!(0, count_k_sums(&[5, 5], 15, 3).unwrap());
+        for k in 0..=list.len() {
+            assert_eq!(values(k).len(), count_k_sums(&list, 10, k).unwrap());
+        }
+    }
+
+    #[test]
+    fn count_in_place_agrees_with_k_sums() {
+        let list = [3, -2, 3, 7, 0, 3, 5, -2, 8, 1, 1, 5];
+        for k in 0..=5 {
+            for target in -4..=20 {
+                let mut numbers = list;
+                let counted = count_k_sums_in_place(&mut numbers, target, k).unwrap();
+
+                assert_eq!(k_sums(&list, target, k).unwrap().count(), counted);
+                assert!(numbers.windows(2).all(|w| w[0] <= w[1]));
+            }
+        }
+    }
+
+    #[cfg(feature = "alloc-profile")]
+    #[test]
+    fn count_in_place_does_not_allocate() {
+        let mut numbers: Vec<i64> = (0..200).map(|n| n * 7 % 101).collect();
+        let (stats, count) =
+            crate::allocs::measure(|| count_k_sums_in_place(&mut numbers, 150, 4).unwrap());
+
+        assert!(count > 0);
+        assert_eq!(0, stats.unwrap().allocations);
+    }
+
+    #[test]
+    fn any_target() {
+        let numbers = [40, 25, 35, 60];
+        let mut events = vec![];
+        assert_eq!(
+            Some(1000),
+            product_of_sum(&numbers, 65, 2, &mut events).ok()
+        );
+        assert!(product_of_sum(&numbers, 1, 4, &mut events).is_err());
+        assert_eq!(
+            vec![
+                Event::new("40 + 25", "sums to 65, product 1000"),
+                Event::new(
+                    "sets of 4",
+                    "none sums to 1, closest is 40 + 25 + 35 + 60 = 160"
+                ),
+            ],
+            events
+        );
+    }
+
+    #[test]
+    fn closest_sum() {
+        let numbers: Numbers = "1\n2\n3\n10".parse().unwrap();
+        let closest = |target, k| numbers.closest_sum(target, k).unwrap().map(|s| s.values);
+
+        assert_eq!(Some(vec![2, 3]), closest(8, 2));
+        assert_eq!(Some(vec![1, 10]), closest(11, 2));
+        assert_eq!(Some(vec![2, 3, 10]), closest(100, 3));
+        assert_eq!(Some(vec![1]), closest(-5, 1));
+        assert_eq!(Some(vec![]), closest(7, 0));
+        assert_eq!(None, closest(7, 5));
+        assert_eq!(
+            Some(60),
+            numbers
+                .closest_sum(16, 3)
+                .unwrap()
+                .and_then(|s| s.product().ok())
+        );
+    }
+
+    #[test]
+    fn sums_between() {
+        let numbers: Numbers = "5\n1\n5\n4\n6".parse().unwrap();
+        let between = |range, k| {
+            numbers
+                .sums_between(range, k)
+                .unwrap()
+                .iter()
+                .map(|s| (s.values.clone(), s.product().ok()))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            vec![
+                (vec![5, 4], Some(20)),
+                (vec![4, 6], Some(24)),
+                (vec![5, 5], Some(25))
+            ],
+            between(9..=10, 2)
+        );
+        assert_eq!(vec![(vec![5, 1, 5, 4, 6], Some(600))], between(0..=21, 5));
+        assert!(between(22..=100, 5).is_empty());
+        assert!(between(RangeInclusive::new(10, 9), 2).is_empty());
+        assert_eq!(vec![(vec![], Some(1))], between(-1..=1, 0));
+    }
+
+    #[test]
+    fn no_answer_and_overflow() {
+        let numbers: Numbers = "1\n2\n3".parse().unwrap();
+        assert_eq!(
+            "day 1 has no answer: no 2 entries sum to 2020",
+            one(&numbers, &mut NoTrace).unwrap_err().to_string()
+        );
+        assert!(two(&numbers, &mut NoTrace).is_err());
+
+        let numbers: Numbers<i32> = "2147483647\n-2147481627\n-1".parse().unwrap();
+        let mut events = vec![];
+        assert_eq!(
+            "day 1: the product of 2147483647 * -2147481627 overflows",
+            one(&numbers, &mut events).unwrap_err().to_string()
+        );
+        assert_eq!(
+            vec![Event::new(
+                "2147483647 + -2147481627",
+                "sums to 2020, product overflows"
+            )],
+            events
+        );
+
+        let numbers: Numbers = "2147483647\n-2147481627\n-1".parse().unwrap();
+        assert_eq!(-4611681676215453669, one(&numbers, &mut NoTrace).unwrap());
+        let numbers: Numbers = "9223372036854775807\n-9223372036854773787".parse().unwrap();
+        assert!(one(&numbers, &mut NoTrace).is_err());
+    }
+
+    #[test]
+    fn wider_entries() {
+        let numbers: Numbers<u64> = "18446744073709551615\n2019\n1".parse().unwrap();
+        assert_eq!(2019, one(&numbers, &mut NoTrace).unwrap());
+        assert_eq!(
+            Some(18446744073709551615 + 2019 + 1),
+            numbers.closest_sum(0, 3).unwrap().map(|s| s.sum().unwrap())
+        );
+
+        let numbers: Numbers<i128> =
+            "1000000000000000000000000000000\n-999999999999999999999999997980"
+                .parse()
+                .unwrap();
+        assert_eq!(
+            2020,
+            find_k_sum(numbers.entries(), 2020, 2)
+                .unwrap()
+                .unwrap()
+                .sum()
+                .unwrap()
+        );
+        assert!(one(&numbers, &mut NoTrace).is_err());
+    }
+
+    #[test]
+    fn too_wide_entries() {
+        let overflow = "day 1: summing the entries overflows";
+        let numbers: Numbers<i128> = "170141183460469231731687303715884105727\n5"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            overflow,
+            one(&numbers, &mut NoTrace).unwrap_err().to_string()
+        );
+        assert_eq!(
+            overflow,
+            find_k_sum(&[i128::MAX, i128::MAX], 0, 2)
+                .unwrap_err()
+                .to_string()
+        );
+        assert!(count_k_sums(&[i128::MIN], 0, 1).is_err());
+        assert!(closest_k_sum(&[1i128], i128::MIN, 1).is_err());
+        assert!(k_sums_between(&[1i128, 2], 0..=i128::MAX, 2).is_err());
+
+        let sum = KSum {
+            indices: vec![0, 1],
+            values: vec![i128::MAX, 1],
+        };
+        assert_eq!(
+            "day 1: the sum of 170141183460469231731687303715884105727 + 1 overflows",
+            sum.sum().unwrap_err().to_string()
+        );
+    }
+
+    /// Fails when read, to show what is found before.
+    struct Endless;
+
+    impl Read for Endless {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            panic!("read too far")
+        }
+    }
+
+    #[test]
+    fn stream_pairs_and_triples() {
+        let example = "1721\n979\n366\n299\n675\n1456\n".as_bytes();
+        let sum = |indices: Vec<usize>, values: Vec<i64>| KSum { indices, values };
+
+        let mut pairs = stream_k_sums(example.chain(Endless), 2020, 2);
+        assert_eq!(
+            sum(vec![0, 3], vec![1721, 299]),
+            pairs.next().unwrap().unwrap()
+        );
+        let mut triples = stream_k_sums(example.chain(Endless), 2020, 3);
+        assert_eq!(
+            sum(vec![1, 2, 4], vec![979, 366, 675]),
+            triples.next().unwrap().unwrap()
+        );
+
+        let mut index = KSumIndex::new(10u64, 3);
+        let pushed: Vec<usize> = [5, 0, 5, 5, 2, 3]
+            .iter()
+            .map(|v| index.push(*v).len())
+            .collect();
+        assert_eq!(vec![0, 0, 1, 0, 0, 1], pushed);
+    }
+
+    #[test]
+    fn stream_errors() {
+        let input = "1010\n\n 1010 \nx\n1010\n".as_bytes();
+        let found: Vec<String> = stream_k_sums(input, 2020i32, 2)
+            .map(|sum| match sum {
+                Ok(sum) => sum.to_string(),
+                Err(e) => e.to_string(),
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                "1010 + 1010",
+                "day 1, line 4: invalid digit found in string in \"x\""
+            ],
+            found
+        );
+    }
+
+    #[cfg(feature = "io")]
+    #[test]
+    fn stream_async() {
+        use async_std::stream::StreamExt;
+
+        let input = "1721\n979\n366\n299\n675\n1456\n".as_bytes();
+        let found = async_std::task::block_on(async {
+            let mut found = vec![];
+            let mut triples = stream_k_sums_async(input, 2020i64, 3);
+            while let Some(sum) = triples.next().await {
+                found.push(sum.unwrap().values);
+            }
+            found
+        });
+        assert_eq!(vec![vec![979, 366, 675]], found);
+    }
+
+    #[test]
+    fn parse_invalid_number() {
+        let err = "1721\n979\n3x6".parse::<Numbers>().unwrap_err();
+        assert_eq!(
+            "day 1, line 3: invalid digit found in string in \"3x6\"",
+            err.to_string()
+        );
+
+        let err = "1721\n\n  -979".parse::<Numbers<u64>>().unwrap_err();
+        assert_eq!(
+            "day 1, line 3: invalid digit found in string in \"-979\"",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn parse_blank_lines_and_whitespace() {
+        let numbers: Numbers = " 1721\n\n\t-979 \r\n  \n+366\n".parse().unwrap();
+        assert_eq!(&[1721, -979, 366], numbers.entries());
     }
 }
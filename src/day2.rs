@@ -1,40 +1,114 @@
+use crate::parse::{Both, Parse};
+use crate::trace::{Event, Trace};
+use crate::{AocError, AocResult, Solution};
+use std::convert::TryFrom;
 use std::ops::RangeInclusive;
 use std::str::FromStr;
 
-pub fn first_step(entries: Passwords<PasswordDay1>) -> i32 {
-    entries.valid_ones().len() as i32
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+
+    type Input<'a> = Both<Passwords<PasswordDay1>, Passwords<PasswordDay2>>;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn part1(input: &Self::Input<'_>, trace: &mut impl Trace) -> AocResult<Self::Part1> {
+        Ok(first_step(&input.0, trace))
+    }
+
+    fn part2(input: &Self::Input<'_>, trace: &mut impl Trace) -> AocResult<Self::Part2> {
+        Ok(second_step(&input.1, trace))
+    }
 }
 
-pub fn second_step(entries: Passwords<PasswordDay2>) -> i32 {
-    entries.valid_ones().len() as i32
+pub fn first_step(entries: &Passwords<PasswordDay1>, trace: &mut impl Trace) -> i32 {
+    entries.valid_ones(trace).len() as i32
+}
+
+pub fn second_step(entries: &Passwords<PasswordDay2>, trace: &mut impl Trace) -> i32 {
+    entries.valid_ones(trace).len() as i32
 }
 
 pub trait ValidatedPassword {
     fn is_valid(&self) -> bool;
+
+    /// What the policy looks at in the password, to explain why it holds or not.
+    fn explain(&self) -> String;
 }
 
 #[derive(Debug)]
 pub struct Passwords<T>(Vec<T>);
 
 impl<T: ValidatedPassword> Passwords<T> {
-    pub fn valid_ones(&self) -> Vec<&T> {
-        self.0.iter().filter(|e| e.is_valid()).collect()
+    pub fn valid_ones(&self, trace: &mut impl Trace) -> Vec<&T> {
+        self.0
+            .iter()
+            .enumerate()
+            .filter(|(i, e)| {
+                let valid = e.is_valid();
+                trace.event(|| {
+                    let verdict = if valid { "valid" } else { "invalid" };
+                    Event::new(
+                        format!("line {}", i + 1),
+                        format!("{}, {}", e.explain(), verdict),
+                    )
+                });
+                valid
+            })
+            .map(|(_, e)| e)
+            .collect()
     }
 }
 
-impl<T: FromStr> FromStr for Passwords<T>
-where
-    <T as FromStr>::Err: std::fmt::Debug,
-{
-    type Err = String;
+impl<T: FromStr<Err = AocError>> FromStr for Passwords<T> {
+    type Err = AocError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(Self(
-            s.lines().map(|line| line.parse::<T>().unwrap()).collect(),
+            s.lines()
+                .enumerate()
+                .map(|(i, line)| line.parse::<T>().map_err(|e| e.shifted(i)))
+                .collect::<AocResult<_>>()?,
         ))
     }
 }
 
+impl<T: FromStr<Err = AocError>> Parse<'_> for Passwords<T> {
+    fn parse(input: &str) -> AocResult<Self> {
+        input.parse()
+    }
+}
+
+/// Splits a `1-3 a: abcde` line into its two policy numbers, the policy char and the password.
+fn parse_policy(s: &str) -> AocResult<(i32, i32, char, &str)> {
+    let invalid = |reason: &str| AocError::parse(Day2::DAY, 1, s, reason);
+    match s.split(':').collect::<Vec<&str>>().as_slice() {
+        [policy_pattern, password] => {
+            match policy_pattern.split(' ').collect::<Vec<&str>>().as_slice() {
+                [range, char] => {
+                    let range_values = range
+                        .split('-')
+                        .map(|v| v.parse::<i32>().map_err(|e| invalid(&e.to_string())))
+                        .collect::<AocResult<Vec<i32>>>()?;
+                    let mut chars = char.chars();
+
+                    match (range_values.as_slice(), chars.next(), chars.next()) {
+                        ([first, second], Some(char), None) => {
+                            Ok((*first, *second, char, password.trim()))
+                        }
+                        ([_, _], _, _) => Err(invalid("the policy must name a single char")),
+                        _ => Err(invalid("the policy must have two numbers")),
+                    }
+                }
+                _ => Err(invalid("no policy could be extracted")),
+            }
+        }
+        _ => Err(invalid("the line is not valid")),
+    }
+}
+
 #[derive(PartialEq, Debug)]
 pub struct PasswordDay1 {
     password: String,
@@ -42,37 +116,40 @@ pub struct PasswordDay1 {
     char: char,
 }
 
+impl PasswordDay1 {
+    fn count(&self) -> usize {
+        self.password.chars().filter(|c| c == &self.char).count()
+    }
+}
+
 impl ValidatedPassword for PasswordDay1 {
     fn is_valid(&self) -> bool {
-        let num_char = self.password.chars().filter(|c| c == &self.char).count();
-        self.occurrences.contains(&(num_char as i32))
+        self.occurrences.contains(&(self.count() as i32))
+    }
+
+    fn explain(&self) -> String {
+        format!(
+            "{:?} has {} {:?}, wants {} to {}",
+            self.password,
+            self.count(),
+            self.char,
+            self.occurrences.start(),
+            self.occurrences.end()
+        )
     }
 }
 
 impl FromStr for PasswordDay1 {
-    type Err = String;
+    type Err = AocError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.split(':').collect::<Vec<&str>>().as_slice() {
-            [policy_pattern, password] => {
-                match policy_pattern.split(' ').collect::<Vec<&str>>().as_slice() {
-                    [range, char] => {
-                        let range_values = range
-                            .split('-')
-                            .map(|v| v.parse::<i32>().unwrap())
-                            .collect::<Vec<i32>>();
-
-                        Ok(Self {
-                            password: password.trim().to_string(),
-                            occurrences: RangeInclusive::new(range_values[0], range_values[1]),
-                            char: char.chars().next().unwrap(),
-                        })
-                    }
-                    _ => Err(format!("no policy could be extracted from {}", s)),
-                }
-            }
-            _ => Err(format!("the line {} is not valid", s)),
-        }
+        let (min, max, char, password) = parse_policy(s)?;
+
+        Ok(Self {
+            password: password.to_string(),
+            occurrences: RangeInclusive::new(min, max),
+            char,
+        })
     }
 }
 
@@ -83,44 +160,48 @@ pub struct PasswordDay2 {
     char: char,
 }
 
+impl PasswordDay2 {
+    /// The char at a position starting from 1, positions outside the password hold none.
+    fn char_at(&self, pos: i32) -> Option<char> {
+        pos.checked_sub(1)
+            .and_then(|i| usize::try_from(i).ok())
+            .and_then(|i| self.password.chars().nth(i))
+    }
+}
+
 impl ValidatedPassword for PasswordDay2 {
     fn is_valid(&self) -> bool {
         let (pos1, pos2) = self.positions;
-        let password_chars: Vec<char> = self.password.chars().collect();
-        let chars = vec![
-            password_chars[(pos1 - 1) as usize],
-            password_chars[(pos2 - 1) as usize],
-        ];
-        chars
-            .iter()
-            .fold(false, |acc, c| if c == &self.char { !acc } else { acc })
+        (self.char_at(pos1) == Some(self.char)) != (self.char_at(pos2) == Some(self.char))
+    }
+
+    fn explain(&self) -> String {
+        let (pos1, pos2) = self.positions;
+        let at = |pos| match self.char_at(pos) {
+            Some(c) => format!("{:?} at {}", c, pos),
+            None => format!("nothing at {}", pos),
+        };
+        format!(
+            "{:?} has {} and {}, wants one {:?}",
+            self.password,
+            at(pos1),
+            at(pos2),
+            self.char
+        )
     }
 }
 
 impl FromStr for PasswordDay2 {
-    type Err = String;
+    type Err = AocError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.split(':').collect::<Vec<&str>>().as_slice() {
-            [policy_pattern, password] => {
-                match policy_pattern.split(' ').collect::<Vec<&str>>().as_slice() {
-                    [range, char] => {
-                        let range_values = range
-                            .split('-')
-                            .map(|v| v.parse::<i32>().unwrap())
-                            .collect::<Vec<i32>>();
-
-                        Ok(Self {
-                            password: password.trim().to_string(),
-                            positions: (range_values[0], range_values[1]),
-                            char: char.chars().next().unwrap(),
-                        })
-                    }
-                    _ => Err(format!("no policy could be extracted from {}", s)),
-                }
-            }
-            _ => Err(format!("the line {} is not valid", s)),
-        }
+        let (pos1, pos2, char, password) = parse_policy(s)?;
+
+        Ok(Self {
+            password: password.to_string(),
+            positions: (pos1, pos2),
+            char,
+        })
     }
 }
 
@@ -141,42 +222,55 @@ mod tests {
     }
 
     #[test]
-    pub fn input_test_1() {
+    pub fn invalid_policy_reports_line() {
         let input = "1-3 a: abcde
-1-3 b: cdefg
+1-3 bb: cdefg
 2-9 c: ccccccccc";
 
-        let entries: Passwords<PasswordDay1> = input.parse().unwrap();
-        assert_eq!(2, entries.valid_ones().len());
+        let err = input.parse::<Passwords<PasswordDay1>>().unwrap_err();
+        assert_eq!(
+            "day 2, line 2: the policy must name a single char in \"1-3 bb: cdefg\"",
+            err.to_string()
+        );
     }
 
     #[test]
-    pub fn input_test_2() {
-        let input = "1-3 a: abcde
-1-3 b: cdefg
-2-9 c: ccccccccccc";
+    pub fn positions_outside_the_password() {
+        let password: PasswordDay2 = "0-6 a: abcde".parse().unwrap();
+        assert!(!password.is_valid());
 
-        let entries: Passwords<PasswordDay1> = input.parse().unwrap();
-        assert_eq!(1, entries.valid_ones().len());
+        let password = PasswordDay2 {
+            password: "abcde".to_string(),
+            positions: (i32::MIN, 1),
+            char: 'a',
+        };
+        assert!(password.is_valid());
     }
 
     #[test]
-    pub fn input_test_3() {
-        let input = "1-3 a: abcde
-1-3 e: cdefg
-2-9 c: ccccccccc";
+    pub fn explain_policies() {
+        let passwords: Passwords<PasswordDay2> = "1-3 a: abcde\n2-9 c: ccccccccc".parse().unwrap();
+        let mut events = vec![];
 
-        let entries: Passwords<PasswordDay1> = input.parse().unwrap();
-        assert_eq!(3, entries.valid_ones().len());
+        assert_eq!(1, second_step(&passwords, &mut events));
+        assert_eq!(
+            vec![
+                Event::new(
+                    "line 1",
+                    "\"abcde\" has 'a' at 1 and 'c' at 3, wants one 'a', valid"
+                ),
+                Event::new(
+                    "line 2",
+                    "\"ccccccccc\" has 'c' at 2 and 'c' at 9, wants one 'c', invalid"
+                ),
+            ],
+            events
+        );
     }
 
     #[test]
-    pub fn input_test_1_new_password_policy() {
-        let input = "1-3 a: abcde
-1-3 b: cdefg
-2-9 c: ccccccccc";
-
-        let entries: Passwords<PasswordDay2> = input.parse().unwrap();
-        assert_eq!(1, entries.valid_ones().len());
+    pub fn missing_policy_number() {
+        assert!("3 a: abcde".parse::<PasswordDay2>().is_err());
+        assert!("1-x a: abcde".parse::<PasswordDay2>().is_err());
     }
 }
@@ -1,108 +1,170 @@
+use crate::parse::Parse;
+use crate::trace::{Event, Trace};
+use crate::{AocError, AocResult, Solution};
 use std::str::FromStr;
 
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+
+    type Input<'a> = Lines;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn part1(input: &Self::Input<'_>, trace: &mut impl Trace) -> AocResult<Self::Part1> {
+        Ok(part1(input, trace))
+    }
+
+    fn part2(input: &Self::Input<'_>, trace: &mut impl Trace) -> AocResult<Self::Part2> {
+        part2(input, trace)
+    }
+}
+
 #[derive(Debug)]
 pub struct Lines(Vec<String>);
 
 impl FromStr for Lines {
-    type Err = ();
+    type Err = AocError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self(s.lines().map(ToString::to_string).collect()))
+        Ok(Self(
+            s.lines()
+                .enumerate()
+                .map(
+                    |(i, line)| match line.chars().find(|c| *c != '.' && *c != '#') {
+                        Some(c) => Err(AocError::parse(
+                            Day3::DAY,
+                            i + 1,
+                            line,
+                            format!("unexpected char {:?}", c),
+                        )),
+                        None => Ok(line.to_string()),
+                    },
+                )
+                .collect::<AocResult<_>>()?,
+        ))
     }
 }
 
-fn traverse_slope(lines: &[String], right: usize, down: usize) -> i64 {
-    let (trees, _) = lines
-        .iter()
-        .step_by(down)
-        .fold((0, 0), |(trees, line_p), row| {
-            let element = row.chars().cycle().nth(line_p);
-            (
-                trees + (element.map(|el| if el == '#' { 1 } else { 0 }).unwrap_or(0)),
-                line_p + right,
-            )
-        });
+impl Parse<'_> for Lines {
+    fn parse(input: &str) -> AocResult<Self> {
+        input.parse()
+    }
+}
+
+fn traverse_slope(lines: &[String], right: usize, down: usize, trace: &mut impl Trace) -> i64 {
+    let slope = || format!("slope right {}, down {}", right, down);
+    let (trees, _) =
+        lines
+            .iter()
+            .enumerate()
+            .step_by(down)
+            .fold((0, 0), |(trees, line_p), (row_i, row)| {
+                let element = row.chars().cycle().nth(line_p);
+                if element == Some('#') {
+                    trace.event(|| {
+                        let column = line_p % row.chars().count() + 1;
+                        Event::new(
+                            slope(),
+                            format!("tree at row {}, column {}", row_i + 1, column),
+                        )
+                    });
+                }
+                (
+                    trees + (element.map(|el| if el == '#' { 1 } else { 0 }).unwrap_or(0)),
+                    line_p + right,
+                )
+            });
+    trace.event(|| Event::new(slope(), format!("{} trees", trees)));
     trees
 }
 
-pub fn part1(lines: Lines) -> i64 {
-    traverse_slope(lines.0.as_slice(), 3, 1)
+pub fn part1(lines: &Lines, trace: &mut impl Trace) -> i64 {
+    traverse_slope(lines.0.as_slice(), 3, 1, trace)
 }
 
-pub fn part2(lines: Lines) -> i64 {
-    let run1 = traverse_slope(lines.0.as_slice(), 1, 1);
-    let run2 = traverse_slope(lines.0.as_slice(), 3, 1);
-    let run3 = traverse_slope(lines.0.as_slice(), 5, 1);
-    let run4 = traverse_slope(lines.0.as_slice(), 7, 1);
-    let run5 = traverse_slope(lines.0.as_slice(), 1, 2);
+/// The product of the trees met on every slope. Fails with an overflow error when it does
+/// not fit in an `i64`.
+pub fn part2(lines: &Lines, trace: &mut impl Trace) -> AocResult<i64> {
+    let run1 = traverse_slope(lines.0.as_slice(), 1, 1, trace);
+    let run2 = traverse_slope(lines.0.as_slice(), 3, 1, trace);
+    let run3 = traverse_slope(lines.0.as_slice(), 5, 1, trace);
+    let run4 = traverse_slope(lines.0.as_slice(), 7, 1, trace);
+    let run5 = traverse_slope(lines.0.as_slice(), 1, 2, trace);
 
-    run1 * run2 * run3 * run4 * run5
+    [run2, run3, run4, run5]
+        .iter()
+        .try_fold(run1, |product, run| product.checked_mul(*run))
+        .ok_or_else(|| AocError::overflow(Day3::DAY, "the product of the trees"))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::trace::NoTrace;
 
     #[test]
     fn run1() {
         let lines: Lines = input().parse().unwrap();
 
-        assert_eq!(2, traverse_slope(lines.0.as_slice(), 1, 1));
+        assert_eq!(2, traverse_slope(lines.0.as_slice(), 1, 1, &mut NoTrace));
     }
 
     #[test]
     fn run2() {
         let lines: Lines = input().parse().unwrap();
 
-        assert_eq!(7, traverse_slope(lines.0.as_slice(), 3, 1));
+        assert_eq!(7, traverse_slope(lines.0.as_slice(), 3, 1, &mut NoTrace));
     }
 
     #[test]
     fn run3() {
         let lines: Lines = input().parse().unwrap();
 
-        assert_eq!(3, traverse_slope(lines.0.as_slice(), 5, 1));
+        assert_eq!(3, traverse_slope(lines.0.as_slice(), 5, 1, &mut NoTrace));
     }
 
     #[test]
     fn run4() {
         let lines: Lines = input().parse().unwrap();
 
-        assert_eq!(4, traverse_slope(lines.0.as_slice(), 7, 1));
+        assert_eq!(4, traverse_slope(lines.0.as_slice(), 7, 1, &mut NoTrace));
     }
 
     #[test]
     fn run5() {
         let lines: Lines = input().parse().unwrap();
 
-        assert_eq!(2, traverse_slope(lines.0.as_slice(), 1, 2));
+        assert_eq!(2, traverse_slope(lines.0.as_slice(), 1, 2, &mut NoTrace));
     }
 
     #[test]
-    fn product() {
-        let lines: Lines = input().parse().unwrap();
+    fn explain_trees_hit() {
+        let lines: Lines = "#..\n.#.\n.#.".parse().unwrap();
+        let mut events = vec![];
 
+        assert_eq!(2, traverse_slope(lines.0.as_slice(), 1, 2, &mut events));
+        assert_eq!(
+            vec![
+                Event::new("slope right 1, down 2", "tree at row 1, column 1"),
+                Event::new("slope right 1, down 2", "tree at row 3, column 2"),
+                Event::new("slope right 1, down 2", "2 trees"),
+            ],
+            events
+        );
+    }
+
+    #[test]
+    fn invalid_map_char() {
+        let err = "..#\n.x.".parse::<Lines>().unwrap_err();
         assert_eq!(
-            336,
-            traverse_slope(lines.0.as_slice(), 1, 1)
-                * traverse_slope(lines.0.as_slice(), 3, 1)
-                * traverse_slope(lines.0.as_slice(), 5, 1)
-                * traverse_slope(lines.0.as_slice(), 7, 1)
-                * traverse_slope(lines.0.as_slice(), 1, 2)
+            "day 3, line 2: unexpected char 'x' in \".x.\"",
+            err.to_string()
         );
     }
 
     fn input() -> &'static str {
-        "..##.......
-#...#...#..
-.#....#..#.
-..#.#...#.#
-.#...##..#.
-..#.##.....
-.#.#.#....#
-.#........#
-#.##...#...
-#...##....#
-.#..#...#.#"
+        include_str!("../input/examples/day3_example.txt")
     }
 }
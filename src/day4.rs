@@ -1,34 +1,122 @@
+use crate::parse::Both;
+use crate::trace::{Event, Trace};
+use crate::{AocError, AocResult, Solution};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::collections::HashMap;
 use std::convert::{TryFrom, TryInto};
+use std::fmt;
 use std::ops::RangeBounds;
 
 static REGEX_COLOR: Lazy<Regex> = Lazy::new(|| Regex::new(r"^#[a-f0-9]{6}$").unwrap());
 static REGEX_PID: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\d{9}$").unwrap());
 static REGEX_HEIGHT: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\d+)(cm|in)$").unwrap());
 
-pub fn part1(passports: Passports<PassportData>) -> u32 {
-    passports.0.iter().filter(|p| p.is_valid()).count() as u32
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+
+    type Input<'a> = Both<Passports<PassportData<'a>>, Passports<PassportDataStrict<'a>>>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn part1(input: &Self::Input<'_>, trace: &mut impl Trace) -> AocResult<Self::Part1> {
+        Ok(part1(&input.0, trace))
+    }
+
+    fn part2(input: &Self::Input<'_>, trace: &mut impl Trace) -> AocResult<Self::Part2> {
+        Ok(part2(&input.1, trace))
+    }
+}
+
+pub fn part1(passports: &Passports<PassportData>, trace: &mut impl Trace) -> u32 {
+    count_valid(passports, PassportData::rejection, trace)
+}
+
+pub fn part2(passports: &Passports<PassportDataStrict>, trace: &mut impl Trace) -> u32 {
+    count_valid(passports, PassportDataStrict::rejection, trace)
+}
+
+/// Counts the passports nothing rejects, telling why the other ones are rejected.
+fn count_valid<'a, T>(
+    passports: &'a Passports<T>,
+    rejection: impl Fn(&'a T) -> Option<Rejection<'a>>,
+    trace: &mut impl Trace,
+) -> u32 {
+    passports
+        .0
+        .iter()
+        .enumerate()
+        .filter(|(i, passport)| {
+            let rejection = rejection(passport);
+            trace.event(|| match &rejection {
+                Some(rejection) => Event::new(format!("passport {}", i + 1), rejection),
+                None => Event::new(format!("passport {}", i + 1), "valid"),
+            });
+            rejection.is_none()
+        })
+        .count() as u32
+}
+
+const REQUIRED_FIELDS: [&str; 7] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"];
+
+/// Why a passport is invalid: the first required field that is missing or breaks its rule.
+#[derive(Debug, PartialEq)]
+pub enum Rejection<'a> {
+    Missing(&'static str),
+    Invalid(&'static str, &'a str),
 }
 
-pub fn part2(passports: Passports<PassportDataStrict>) -> u32 {
-    passports.0.iter().filter(|p| p.is_valid()).count() as u32
+impl fmt::Display for Rejection<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rejection::Missing(field) => write!(f, "missing {}", field),
+            Rejection::Invalid(field, value) => write!(f, "invalid {} {:?}", field, value),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct Passports<T>(Vec<T>);
 
-impl<'a> From<&'a str> for Passports<PassportData<'a>> {
-    fn from(s: &'a str) -> Self {
-        Self(s.split("\n\n").map(Into::into).collect())
+impl<'a, T: TryFrom<&'a str, Error = AocError>> TryFrom<&'a str> for Passports<T> {
+    type Error = AocError;
+
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        let mut line = 0;
+        let passports = s
+            .split("\n\n")
+            .map(|block| {
+                let passport = T::try_from(block).map_err(|e| e.shifted(line));
+                line += block.lines().count() + 1;
+                passport
+            })
+            .collect::<AocResult<_>>()?;
+
+        Ok(Self(passports))
     }
 }
 
-impl<'a> From<&'a str> for Passports<PassportDataStrict<'a>> {
-    fn from(s: &'a str) -> Self {
-        Self(s.split("\n\n").map(Into::into).collect())
+/// Reads the `key:value` pairs of a passport, which may span several lines.
+fn parse_fields(s: &str) -> AocResult<HashMap<&str, &str>> {
+    let mut fields = HashMap::new();
+    for (i, line) in s.lines().enumerate() {
+        for data in line.split_whitespace() {
+            match data.split(':').collect::<Vec<&str>>().as_slice() {
+                [key, value] => fields.insert(*key, *value),
+                _ => {
+                    return Err(AocError::parse(
+                        Day4::DAY,
+                        i + 1,
+                        data,
+                        "a field must be in the form key:value",
+                    ))
+                }
+            };
+        }
     }
+    Ok(fields)
 }
 
 #[derive(Debug, PartialEq)]
@@ -36,27 +124,22 @@ pub struct PassportData<'a>(HashMap<&'a str, &'a str>);
 
 impl<'a> PassportData<'a> {
     pub fn is_valid(&self) -> bool {
-        self.0.get("byr").is_some()
-            && self.0.get("iyr").is_some()
-            && self.0.get("eyr").is_some()
-            && self.0.get("hgt").is_some()
-            && self.0.get("hcl").is_some()
-            && self.0.get("ecl").is_some()
-            && self.0.get("pid").is_some()
+        self.rejection().is_none()
+    }
+
+    pub fn rejection(&self) -> Option<Rejection<'a>> {
+        REQUIRED_FIELDS
+            .iter()
+            .find(|field| !self.0.contains_key(*field))
+            .map(|field| Rejection::Missing(field))
     }
 }
 
-impl<'a> From<&'a str> for PassportData<'a> {
-    fn from(s: &'a str) -> Self {
-        let fields: HashMap<&str, &str> = s
-            .split_whitespace()
-            .map(|data| {
-                let parts: Vec<&str> = data.split(':').collect();
-                (parts[0], parts[1])
-            })
-            .collect();
+impl<'a> TryFrom<&'a str> for PassportData<'a> {
+    type Error = AocError;
 
-        Self(fields)
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        Ok(Self(parse_fields(s)?))
     }
 }
 
@@ -65,49 +148,27 @@ pub struct PassportDataStrict<'a>(HashMap<&'a str, &'a str>);
 
 impl<'a> PassportDataStrict<'a> {
     pub fn is_valid(&self) -> bool {
-        let valid_byr = self
-            .0
-            .get("byr")
-            .map(|y| Self::validate_num(y, 1920..=2002))
-            .unwrap_or_default();
-
-        let valid_iyr = self
-            .0
-            .get("iyr")
-            .map(|y| Self::validate_num(y, 2010..=2020))
-            .unwrap_or_default();
-
-        let valid_eyr = self
-            .0
-            .get("eyr")
-            .map(|y| Self::validate_num(y, 2020..=2030))
-            .unwrap_or_default();
-
-        let valid_hgt = self
-            .0
-            .get("hgt")
-            .map(|h| Self::validate_height(h))
-            .unwrap_or_default();
-
-        let valid_hcl = self
-            .0
-            .get("hcl")
-            .map(|hcl| REGEX_COLOR.is_match(hcl))
-            .unwrap_or_default();
-
-        let valid_ecl = self
-            .0
-            .get("ecl")
-            .map(|ecl| ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"].contains(ecl))
-            .unwrap_or_default();
-
-        let valid_pid = self
-            .0
-            .get("pid")
-            .map(|pid| REGEX_PID.is_match(pid))
-            .unwrap_or_default();
-
-        valid_byr && valid_iyr && valid_eyr && valid_hgt && valid_hcl && valid_ecl && valid_pid
+        self.rejection().is_none()
+    }
+
+    pub fn rejection(&self) -> Option<Rejection<'a>> {
+        let rules: [fn(&str) -> bool; 7] = [
+            |y| Self::validate_num(y, 1920..=2002),
+            |y| Self::validate_num(y, 2010..=2020),
+            |y| Self::validate_num(y, 2020..=2030),
+            Self::validate_height,
+            |hcl| REGEX_COLOR.is_match(hcl),
+            |ecl| ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"].contains(&ecl),
+            |pid| REGEX_PID.is_match(pid),
+        ];
+        REQUIRED_FIELDS
+            .iter()
+            .zip(rules.iter())
+            .find_map(|(field, valid)| match self.0.get(field) {
+                None => Some(Rejection::Missing(field)),
+                Some(value) if !valid(value) => Some(Rejection::Invalid(field, value)),
+                Some(_) => None,
+            })
     }
 
     fn validate_num(num: &str, range: impl RangeBounds<i32>) -> bool {
@@ -145,29 +206,21 @@ impl<'a> TryFrom<&'a str> for Height {
     type Error = ();
 
     fn try_from(value: &'a str) -> Result<Self, Self::Error> {
-        let caps = REGEX_HEIGHT.captures(value);
-        caps.map(|c| {
-            if c.get(2).unwrap().as_str() == "cm" {
-                Ok(Self::Cm(c.get(1).unwrap().as_str().parse().unwrap()))
-            } else {
-                Ok(Self::In(c.get(1).unwrap().as_str().parse().unwrap()))
-            }
-        })
-        .unwrap_or_else(|| Err(()))
+        // `\d` matches any unicode digit, and any number of them
+        let caps = REGEX_HEIGHT.captures(value).ok_or(())?;
+        let height = caps[1].parse().map_err(|_| ())?;
+        match &caps[2] {
+            "cm" => Ok(Self::Cm(height)),
+            _ => Ok(Self::In(height)),
+        }
     }
 }
 
-impl<'a> From<&'a str> for PassportDataStrict<'a> {
-    fn from(s: &'a str) -> Self {
-        let fields: HashMap<&str, &str> = s
-            .split_whitespace()
-            .map(|data| {
-                let parts: Vec<&str> = data.split(':').collect();
-                (parts[0], parts[1])
-            })
-            .collect();
+impl<'a> TryFrom<&'a str> for PassportDataStrict<'a> {
+    type Error = AocError;
 
-        Self(fields)
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        Ok(Self(parse_fields(s)?))
     }
 }
 
@@ -176,111 +229,43 @@ mod tests {
     use super::*;
 
     #[test]
-    pub fn parse_data_1() {
-        let data = "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
-byr:1937 iyr:2017 cid:147 hgt:183cm";
-        let passport_data: PassportData = data.into();
-
-        assert!(passport_data.is_valid());
-    }
-
-    #[test]
-    pub fn parse_data_2() {
-        let data = "iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884
-hcl:#cfa07d byr:1929";
-        let passport_data: PassportData = data.into();
-
-        assert!(!passport_data.is_valid());
-    }
-
-    #[test]
-    pub fn test_invalid_passport_1() {
-        let s = "eyr:1972 cid:100
-hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926";
-        let pd: PassportDataStrict = s.into();
-
-        assert!(!pd.is_valid());
-    }
-
-    #[test]
-    pub fn test_invalid_passport_2() {
-        let s = "iyr:2019
-hcl:#602927 eyr:1967 hgt:170cm
-ecl:grn pid:012533040 byr:1946";
-        let pd: PassportDataStrict = s.into();
-
-        assert!(!pd.is_valid());
-    }
-
-    #[test]
-    pub fn test_invalid_passport_3() {
-        let s = "hcl:dab227 iyr:2012
-ecl:brn hgt:182cm pid:021572410 eyr:2020 byr:1992 cid:277";
-        let pd: PassportDataStrict = s.into();
-
-        assert!(!pd.is_valid());
-    }
-
-    #[test]
-    pub fn test_invalid_passport_4() {
-        let s = "hgt:59cm ecl:zzz
-eyr:2038 hcl:74454a iyr:2023
-pid:3556412378 byr:2007";
-        let pd: PassportDataStrict = s.into();
-
-        assert!(!pd.is_valid());
+    pub fn unparsable_heights_are_invalid() {
+        assert!(!PassportDataStrict::validate_height("99999999999cm"));
+        assert!(!PassportDataStrict::validate_height("١٦٠cm"));
+        assert!(PassportDataStrict::validate_height("160cm"));
     }
 
     #[test]
-    pub fn test_valid_passport_1() {
-        let s = "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980
-hcl:#623a2f";
-        let pd: PassportDataStrict = s.into();
-
-        assert!(pd.is_valid());
+    pub fn explain_rejections() {
+        let passports = Passports::<PassportDataStrict>::try_from(
+            "byr:1920 iyr:2010 eyr:2020 hgt:190in hcl:#123abc ecl:brn pid:000000001
+
+byr:1920 iyr:2010 eyr:2020 hgt:60in hcl:#123abc pid:000000001",
+        )
+        .unwrap();
+        let mut events = vec![];
+
+        assert_eq!(0, part2(&passports, &mut events));
+        assert_eq!(
+            vec![
+                Event::new("passport 1", "invalid hgt \"190in\""),
+                Event::new("passport 2", "missing ecl"),
+            ],
+            events
+        );
     }
 
     #[test]
-    pub fn test_valid_passport_2() {
-        let s = "eyr:2029 ecl:blu cid:129 byr:1989
-iyr:2014 pid:896056539 hcl:#a97842 hgt:165cm";
-        let pd: PassportDataStrict = s.into();
-
-        assert!(pd.is_valid());
-    }
+    pub fn invalid_field_reports_line() {
+        let s = "iyr:2010 hgt:158cm
 
-    #[test]
-    pub fn test_valid_passport_3() {
-        let s = "hcl:#888785
-hgt:164cm byr:2001 iyr:2015 cid:88
-pid:545766238 ecl:hzl
-eyr:2022";
-        let pd: PassportDataStrict = s.into();
-
-        assert!(pd.is_valid());
-    }
-
-    #[test]
-    pub fn test_valid_passport_4() {
-        let s = "iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719";
-        let pd: PassportDataStrict = s.into();
-
-        assert!(pd.is_valid());
-    }
-
-    #[test]
-    pub fn test_valid_passport_5() {
-        let s = "iyr:2010 hgt:193cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719";
-        let pd: PassportDataStrict = s.into();
-
-        assert!(pd.is_valid());
-    }
-
-    #[test]
-    pub fn test_valid_passport_6() {
-        let s = "iyr:2010 hgt:59in hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719";
-        let pd: PassportDataStrict = s.into();
+hcl:#b6652a ecl:blu
+byr:1944 eyr pid:093154719";
+        let err = Passports::<PassportData>::try_from(s).unwrap_err();
 
-        assert!(pd.is_valid());
+        assert_eq!(
+            "day 4, line 4: a field must be in the form key:value in \"eyr\"",
+            err.to_string()
+        );
     }
 }
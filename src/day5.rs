@@ -1,43 +1,99 @@
-use std::iter::Peekable;
+use crate::trace::{Event, Trace};
+use crate::{AocError, AocResult, Solution};
+use std::convert::TryFrom;
 use std::ops::RangeInclusive;
 
 #[derive(Debug)]
 pub struct Positions<'a>(Vec<&'a str>);
 
-impl<'a> From<&'a str> for Positions<'a> {
-    fn from(s: &'a str) -> Self {
-        Self(s.lines().collect())
+impl<'a> TryFrom<&'a str> for Positions<'a> {
+    type Error = AocError;
+
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        Ok(Self(
+            s.lines()
+                .enumerate()
+                .map(|(i, line)| validate_position(line).map_err(|e| e.shifted(i)))
+                .collect::<AocResult<_>>()?,
+        ))
     }
 }
 
-pub fn part1(positions: Positions) -> u32 {
+/// A boarding pass is 7 `F`/`B` chars for the row followed by 3 `L`/`R` chars for the column.
+fn validate_position(pos: &str) -> AocResult<&str> {
+    let invalid = |reason: &str| Err(AocError::parse(Day5::DAY, 1, pos, reason));
+    if pos.chars().count() != 10 {
+        return invalid("a boarding pass must be 10 chars long");
+    }
+    let (row, col) = pos.split_at(pos.char_indices().nth(7).map_or(pos.len(), |(i, _)| i));
+    if !row.chars().all(|c| c == 'F' || c == 'B') {
+        return invalid("the row must be made of F and B");
+    }
+    if !col.chars().all(|c| c == 'L' || c == 'R') {
+        return invalid("the column must be made of L and R");
+    }
+    Ok(pos)
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+
+    type Input<'a> = Positions<'a>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn part1(input: &Self::Input<'_>, trace: &mut impl Trace) -> AocResult<Self::Part1> {
+        part1(input, trace)
+            .ok_or_else(|| AocError::no_answer(Self::DAY, "there are no boarding passes"))
+    }
+
+    fn part2(input: &Self::Input<'_>, trace: &mut impl Trace) -> AocResult<Self::Part2> {
+        part2(input, trace)
+            .ok_or_else(|| AocError::no_answer(Self::DAY, "no seat is missing between two others"))
+    }
+}
+
+pub fn part1(positions: &Positions, trace: &mut impl Trace) -> Option<u32> {
     positions
         .0
         .iter()
-        .map(|pos| calculate_id(pos))
+        .map(|pos| {
+            let id = calculate_id(pos);
+            trace.event(|| {
+                let seat = format!("row {}, column {}", calculate_row(pos), calculate_col(pos));
+                Event::new(pos, format!("{}, id {}", seat, id))
+            });
+            id
+        })
         .max()
-        .unwrap()
 }
 
-pub fn part2(positions: Positions) -> u32 {
+pub fn part2(positions: &Positions, trace: &mut impl Trace) -> Option<u32> {
     let mut ids = positions
         .0
         .iter()
         .map(|pos| calculate_id(pos))
         .collect::<Vec<u32>>();
-    ids.sort();
-    let mut iterator = ids.iter().peekable();
-    find_non_consecutive_numbers(&mut iterator)
+    ids.sort_unstable();
+    ids.dedup();
+    let missing = find_non_consecutive_numbers(&ids);
+    trace.event(|| match missing {
+        Some(id) => Event::new(
+            format!("seat {}", id),
+            format!("free, between {} and {}", id - 1, id + 1),
+        ),
+        None => Event::new("seats", format!("none free among {} taken", ids.len())),
+    });
+    missing
 }
 
-fn find_non_consecutive_numbers(iterator: &mut Peekable<std::slice::Iter<u32>>) -> u32 {
-    let actual = iterator.next().unwrap();
-    let next = iterator.peek().unwrap();
-
-    if *actual + 1 != **next {
-        return *actual + 1;
-    }
-    find_non_consecutive_numbers(iterator)
+/// The first id missing from sorted `ids`, between two that are there.
+fn find_non_consecutive_numbers(ids: &[u32]) -> Option<u32> {
+    ids.windows(2)
+        .find(|pair| pair[0] + 1 != pair[1])
+        .map(|pair| pair[0] + 1)
 }
 
 fn calculate_row(pos: &str) -> u32 {
@@ -79,6 +135,7 @@ fn calculate_id(pos: &str) -> u32 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::trace::NoTrace;
 
     #[test]
     pub fn pos_1() {
@@ -103,4 +160,29 @@ mod tests {
         assert_eq!(4, calculate_col(pos));
         assert_eq!(820, calculate_id(pos));
     }
+
+    #[test]
+    pub fn invalid_positions() {
+        let err = Positions::try_from("BFFFBBFRRR\nBFFFBBFRR").unwrap_err();
+        assert_eq!(
+            "day 5, line 2: a boarding pass must be 10 chars long in \"BFFFBBFRR\"",
+            err.to_string()
+        );
+        assert!(Positions::try_from("BFFFBBLRRR").is_err());
+        assert!(Positions::try_from("BFFFBBFRRB").is_err());
+        assert!(Positions::try_from("BFFFBB\u{e9}LR").is_err());
+    }
+
+    #[test]
+    pub fn no_seat_missing() {
+        let positions = Positions::try_from("").unwrap();
+        assert_eq!(None, part1(&positions, &mut NoTrace));
+        assert_eq!(None, part2(&positions, &mut NoTrace));
+
+        let positions = Positions::try_from("BFFFBBFRRR\nBFFFBBFRRR\nBFFFBBFRRL").unwrap();
+        let mut events = vec![];
+        assert_eq!(Some(567), part1(&positions, &mut NoTrace));
+        assert_eq!(None, part2(&positions, &mut events));
+        assert_eq!(vec![Event::new("seats", "none free among 2 taken")], events);
+    }
 }
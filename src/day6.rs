@@ -1,3 +1,7 @@
+use crate::trace::{Event, Trace};
+use crate::{AocError, AocResult, Solution};
+use std::convert::TryFrom;
+
 #[derive(Debug)]
 pub struct Person {
     answers: Vec<char>,
@@ -52,43 +56,86 @@ impl Group {
 #[derive(Debug)]
 pub struct Groups(Vec<Group>);
 
-impl From<&str> for Groups {
-    fn from(s: &str) -> Self {
-        Self(s.split("\n\n").map(Group::from_lines).collect())
+impl TryFrom<&str> for Groups {
+    type Error = AocError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        for (i, line) in s.lines().enumerate() {
+            if let Some(c) = line.chars().find(|c| !c.is_ascii_lowercase()) {
+                return Err(AocError::parse(
+                    Day6::DAY,
+                    i + 1,
+                    line,
+                    format!("unexpected answer {:?}", c),
+                ));
+            }
+        }
+        Ok(Self(s.split("\n\n").map(Group::from_lines).collect()))
+    }
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+
+    type Input<'a> = Groups;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn part1(input: &Self::Input<'_>, trace: &mut impl Trace) -> AocResult<Self::Part1> {
+        Ok(part1(input, trace))
+    }
+
+    fn part2(input: &Self::Input<'_>, trace: &mut impl Trace) -> AocResult<Self::Part2> {
+        Ok(part2(input, trace))
     }
 }
 
-pub fn part1(groups: Groups) -> usize {
-    groups.0.iter().map(|g| g.all_unique_answers().len()).sum()
+pub fn part1(groups: &Groups, trace: &mut impl Trace) -> usize {
+    count_answers(groups, "anyone", Group::all_unique_answers, trace)
 }
 
-pub fn part2(groups: Groups) -> usize {
-    groups.0.iter().map(|g| g.all_same_answer().len()).sum()
+pub fn part2(groups: &Groups, trace: &mut impl Trace) -> usize {
+    count_answers(groups, "everyone", Group::all_same_answer, trace)
+}
+
+/// Sums the questions `answered` picks in every group, telling which they are.
+fn count_answers(
+    groups: &Groups,
+    by: &str,
+    answered: fn(&Group) -> Vec<&char>,
+    trace: &mut impl Trace,
+) -> usize {
+    groups
+        .0
+        .iter()
+        .enumerate()
+        .map(|(i, group)| {
+            let answers = answered(group);
+            trace.event(|| {
+                let questions: String = answers.iter().copied().collect();
+                Event::new(
+                    format!("group {}", i + 1),
+                    format!("{} answered by {}: {}", answers.len(), by, questions),
+                )
+            });
+            answers.len()
+        })
+        .sum()
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day6::{part1, Groups};
+    use crate::day6::Groups;
+    use std::convert::TryInto;
 
     #[test]
-    fn test1() {
-        let input = "abc
-
-a
-b
-c
-
-ab
-ac
-
-a
-a
-a
-a
-
-b";
-        let groups: Groups = input.into();
-        assert_eq!(5, groups.0.len());
-        assert_eq!(11, part1(groups));
+    fn invalid_answer() {
+        let groups: Result<Groups, _> = "abc\n\naB".try_into();
+        assert_eq!(
+            "day 6, line 3: unexpected answer 'B' in \"aB\"",
+            groups.unwrap_err().to_string()
+        );
     }
 }
@@ -1,19 +1,51 @@
+use crate::trace::{Event, Trace};
+use crate::{AocError, AocResult, Solution};
 use once_cell::sync::Lazy;
 use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::convert::{TryFrom, TryInto};
 
-pub fn part1(bags: Bags) -> usize {
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+
+    type Input<'a> = Bags;
+    type Part1 = usize;
+    type Part2 = i32;
+
+    fn part1(input: &Self::Input<'_>, trace: &mut impl Trace) -> AocResult<Self::Part1> {
+        Ok(part1(input, trace))
+    }
+
+    fn part2(input: &Self::Input<'_>, trace: &mut impl Trace) -> AocResult<Self::Part2> {
+        part2(input, trace).ok_or_else(|| {
+            AocError::no_answer(
+                Self::DAY,
+                "there is no shiny gold bag, or it holds infinitely many bags",
+            )
+        })
+    }
+}
+
+pub fn part1(bags: &Bags, trace: &mut impl Trace) -> usize {
     let mut can_contain = 0;
     for bag in &bags.0 {
-        if bag.can_contain("shiny gold", &bags) {
+        if bag.can_contain("shiny gold", bags, trace) {
             can_contain += 1;
         }
     }
     can_contain
 }
 
-pub fn part2(bags: Bags) -> i32 {
-    let shiny_gold = bags.find_color("shiny gold").unwrap();
-    shiny_gold.count_content(&bags, 1)
+pub fn part2(bags: &Bags, trace: &mut impl Trace) -> Option<i32> {
+    match bags.find_color("shiny gold") {
+        Some(bag) => bag.count_content(bags, trace),
+        None => {
+            trace.event(|| Event::new("shiny gold", "has no rule"));
+            None
+        }
+    }
 }
 
 static REGEX_CONTAINS: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\d+)\s(.+)$").unwrap());
@@ -27,9 +59,16 @@ impl Bags {
     }
 }
 
-impl From<&str> for Bags {
-    fn from(s: &str) -> Self {
-        Bags(s.lines().map(Into::into).collect())
+impl TryFrom<&str> for Bags {
+    type Error = AocError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Ok(Bags(
+            s.lines()
+                .enumerate()
+                .map(|(i, line)| Bag::try_from(line).map_err(|e| e.shifted(i)))
+                .collect::<AocResult<_>>()?,
+        ))
     }
 }
 
@@ -40,48 +79,99 @@ pub struct Bag {
 }
 
 impl Bag {
-    fn all_colors<'a>(&'a self, bags: &'a Bags) -> Vec<&'a str> {
-        self.contain
-            .0
-            .iter()
-            .map(|(_, color)| color.as_str())
-            .flat_map(|c| {
-                let mut output_colors = vec![c];
-                output_colors.append(
-                    &mut bags
-                        .find_color(c)
-                        .map(|b| b.all_colors(bags))
-                        .unwrap_or_default(),
-                );
-                output_colors
-            })
-            .collect()
+    /// Whether a bag of `color` can end up inside this one, however deep. Every color is
+    /// looked into once, so rules holding each other are fine.
+    pub fn can_contain(&self, color: &str, bags: &Bags, trace: &mut impl Trace) -> bool {
+        let mut seen = HashSet::new();
+        let mut stack = vec![self];
+        while let Some(bag) = stack.pop() {
+            if bag.color != self.color {
+                trace.event(|| Event::new(&self.color, format!("looks into {}", bag.color)));
+            }
+            for (_, inner) in &bag.contain.0 {
+                if inner == color {
+                    trace.event(|| match bag.color == self.color {
+                        true => Event::new(&self.color, format!("holds {}", color)),
+                        false => {
+                            Event::new(&self.color, format!("holds {} inside {}", color, bag.color))
+                        }
+                    });
+                    return true;
+                }
+                if seen.insert(inner.as_str()) {
+                    stack.extend(bags.find_color(inner));
+                }
+            }
+        }
+        trace.event(|| Event::new(&self.color, format!("can't hold {}", color)));
+        false
     }
 
-    pub fn can_contain(&self, color: &str, bags: &Bags) -> bool {
-        self.all_colors(bags).contains(&color)
-    }
+    /// How many bags this one holds, or `None` when the rules make a bag hold itself or the
+    /// count overflows. Bags without a rule hold nothing.
+    pub fn count_content(&self, bags: &Bags, trace: &mut impl Trace) -> Option<i32> {
+        // a depth first walk without recursion, counting every bag once after what it holds:
+        // `open` are the bags on the path to the current one
+        let mut counted: HashMap<&str, i32> = HashMap::new();
+        let mut open = HashSet::new();
+        let mut stack = vec![(self, false)];
 
-    pub fn count_content(&self, bags: &Bags, multiplier: i32) -> i32 {
-        self.contain.0.iter().fold(0, |acc, (count, color)| {
-            let bag = bags.find_color(color);
-            let child_count = bag
-                .map(|b| b.count_content(&bags, *count * multiplier))
-                .unwrap_or_default();
-            acc + (count * multiplier) + child_count
-        })
+        while let Some((bag, children_counted)) = stack.pop() {
+            if children_counted {
+                let mut total = Some(0i32);
+                for (count, color) in &bag.contain.0 {
+                    let inner = match bags.find_color(color) {
+                        Some(_) => *counted.get(color.as_str())?,
+                        None => 0,
+                    };
+                    total = total.and_then(|total| {
+                        total.checked_add(count.checked_mul(inner.checked_add(1)?)?)
+                    });
+                }
+                let total = match total {
+                    Some(total) => total,
+                    None => {
+                        trace.event(|| Event::new(&bag.color, "holds too many bags to count"));
+                        return None;
+                    }
+                };
+                trace.event(|| Event::new(&bag.color, format!("holds {} bags", total)));
+                open.remove(bag.color.as_str());
+                counted.insert(&bag.color, total);
+            } else if !counted.contains_key(bag.color.as_str()) {
+                open.insert(bag.color.as_str());
+                stack.push((bag, true));
+                for (_, color) in &bag.contain.0 {
+                    if open.contains(color.as_str()) {
+                        trace.event(|| {
+                            Event::new(color, format!("holds itself through {}", bag.color))
+                        });
+                        return None;
+                    }
+                    stack.extend(bags.find_color(color).map(|inner| (inner, false)));
+                }
+            }
+        }
+        counted.get(self.color.as_str()).copied()
     }
 }
 
-impl From<&str> for Bag {
-    fn from(s: &str) -> Self {
+impl TryFrom<&str> for Bag {
+    type Error = AocError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
         let contains = s.split("bags contain").collect::<Vec<&str>>();
         match contains.as_slice() {
-            [color, contain] => Self {
+            [color, contain] => Ok(Self {
                 color: color.trim().to_string(),
-                contain: (*contain).into(),
-            },
-            _ => panic!("invalid input"),
+                contain: (*contain).try_into()?,
+            }),
+            _ => Err(AocError::parse(
+                Day7::DAY,
+                1,
+                s,
+                "a rule must be in the form \"<color> bags contain <content>\"",
+            )),
         }
     }
 }
@@ -89,42 +179,47 @@ impl From<&str> for Bag {
 #[derive(Debug, PartialEq)]
 pub struct Contains(Vec<(i32, String)>);
 
-impl From<&str> for Contains {
-    fn from(s: &str) -> Self {
-        Self(
-            s.split(',')
-                .collect::<Vec<&str>>()
-                .iter()
-                .map(|c| {
-                    c.trim_end_matches('.')
-                        .trim_end_matches("bag")
-                        .trim_end_matches("bags")
-                        .trim()
-                })
-                .flat_map(|c| {
-                    if c == "no other" {
-                        return vec![];
-                    }
-                    let captures = REGEX_CONTAINS.captures(c).unwrap();
-                    vec![(
-                        captures.get(1).unwrap().as_str().parse().unwrap(),
-                        captures.get(2).unwrap().as_str().to_string(),
-                    )]
-                })
-                .collect(),
-        )
+impl TryFrom<&str> for Contains {
+    type Error = AocError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let contents = s
+            .split(',')
+            .map(|c| {
+                c.trim_end_matches('.')
+                    .trim_end_matches("bag")
+                    .trim_end_matches("bags")
+                    .trim()
+            })
+            .filter(|c| *c != "no other")
+            .map(|c| {
+                let invalid = |reason: String| AocError::parse(Day7::DAY, 1, c, reason);
+                let captures = REGEX_CONTAINS.captures(c).ok_or_else(|| {
+                    invalid("a content must be in the form \"<count> <color>\"".to_string())
+                })?;
+                Ok((
+                    captures[1]
+                        .parse()
+                        .map_err(|e| invalid(format!("invalid count: {}", e)))?,
+                    captures[2].to_string(),
+                ))
+            })
+            .collect::<AocResult<_>>()?;
+
+        Ok(Self(contents))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::trace::NoTrace;
 
     #[test]
     pub fn parse_line() {
         let s = "light red bags contain 1 bright white bag, 2 muted yellow bags.";
 
-        let bag: Bag = s.into();
+        let bag: Bag = s.try_into().unwrap();
         assert_eq!(
             Bag {
                 color: "light red".to_string(),
@@ -141,7 +236,7 @@ mod tests {
     pub fn parse_line2() {
         let s = "bright white bags contain 1 shiny gold bag.";
 
-        let bag: Bag = s.into();
+        let bag: Bag = s.try_into().unwrap();
         assert_eq!(
             Bag {
                 color: "bright white".to_string(),
@@ -152,30 +247,50 @@ mod tests {
     }
 
     #[test]
-    pub fn all_containers() {
-        let s = "light red bags contain 1 bright white bag, 2 muted yellow bags.
-dark orange bags contain 3 bright white bags, 4 muted yellow bags.
-bright white bags contain 1 shiny gold bag.
-muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
-shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.
-dark olive bags contain 3 faded blue bags, 4 dotted black bags.
-vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.
-faded blue bags contain no other bags.
-dotted black bags contain no other bags.";
-        let bags: Bags = s.into();
-        assert_eq!(4, part1(bags));
+    pub fn invalid_rules() {
+        let s = "bright white bags contain 1 shiny gold bag.
+muted yellow bags contain two shiny gold bags.";
+        let err = Bags::try_from(s).unwrap_err();
+        assert_eq!(
+            "day 7, line 2: a content must be in the form \"<count> <color>\" in \"two shiny gold\"",
+            err.to_string()
+        );
+        assert!(Bags::try_from("bright white bags hold 1 shiny gold bag.").is_err());
+    }
+
+    #[test]
+    pub fn cyclic_rules() {
+        let bags = Bags::try_from(
+            "shiny gold bags contain 1 dark red bag.
+dark red bags contain 2 shiny gold bags, 1 dim blue bag.
+dim blue bags contain no other bags.",
+        )
+        .unwrap();
+
+        let mut events = vec![];
+        assert_eq!(2, part1(&bags, &mut NoTrace));
+        assert_eq!(None, part2(&bags, &mut events));
+        assert_eq!(
+            vec![Event::new("shiny gold", "holds itself through dark red")],
+            events
+        );
     }
 
     #[test]
-    pub fn count_content() {
-        let s = "shiny gold bags contain 2 dark red bags.
-dark red bags contain 2 dark orange bags.
-dark orange bags contain 2 dark yellow bags.
-dark yellow bags contain 2 dark green bags.
-dark green bags contain 2 dark blue bags.
-dark blue bags contain 2 dark violet bags.
-dark violet bags contain no other bags.";
-        let bags: Bags = s.into();
-        assert_eq!(126, part2(bags));
+    pub fn missing_shiny_gold_and_overflow() {
+        assert_eq!(
+            None,
+            part2(
+                &Bags::try_from("dim blue bags contain 1 red bag.").unwrap(),
+                &mut NoTrace
+            )
+        );
+
+        let bags = Bags::try_from(
+            "shiny gold bags contain 99999 red bags.
+red bags contain 99999 blue bags.",
+        )
+        .unwrap();
+        assert_eq!(None, part2(&bags, &mut NoTrace));
     }
 }
@@ -0,0 +1,31 @@
+//! Tests generated by `build.rs` from the fixtures in `input/examples`.
+
+use crate::answers::Answers;
+use crate::{find_day, Part};
+
+// there are no fixtures to check when every day is disabled
+#[allow(dead_code)]
+fn check_example(name: &str, input: &str, answers: Option<&str>) {
+    let number: u8 = name
+        .strip_prefix("day")
+        .and_then(|n| n.split('_').next())
+        .and_then(|n| n.parse().ok())
+        .unwrap_or_else(|| panic!("{} must be named day<n>_<name>.txt", name));
+    let day = find_day(number).unwrap_or_else(|| panic!("day {} is not registered", number));
+    let answers: Answers = answers
+        .unwrap_or_else(|| panic!("{} has no .answers file", name))
+        .parse()
+        .unwrap();
+
+    let mut checked = 0;
+    for part in Part::BOTH.iter() {
+        if let Some(expected) = answers.get(number, *part) {
+            let outcome = day.run(input, *part).unwrap();
+            assert_eq!(expected, outcome.answer, "{} part {}", name, part.number());
+            checked += 1;
+        }
+    }
+    assert!(checked > 0, "{} has no answer for day {}", name, number);
+}
+
+include!(concat!(env!("OUT_DIR"), "/examples.rs"));
@@ -0,0 +1,634 @@
+//! Random puzzle inputs of any size, together with their answers, to stress test and benchmark
+//! the solvers far beyond the real inputs.
+//!
+//! Every generator builds its input around answers it knows by construction, or computes them
+//! on the side without going through the solver of the day.
+
+use crate::answers::Answers;
+use crate::Part;
+#[cfg(feature = "day7")]
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
+
+/// A SplitMix64 generator: small, fast, and giving the same inputs for the same seed everywhere.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `range`, with a modulo bias too small to matter for puzzle inputs.
+    pub fn range(&mut self, range: RangeInclusive<u64>) -> u64 {
+        let (start, end) = range.into_inner();
+        match (end - start).checked_add(1) {
+            Some(span) => start + self.next_u64() % span,
+            None => self.next_u64(),
+        }
+    }
+
+    /// Whether an event of probability `p` happens.
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0..=items.len() as u64 - 1) as usize]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0..=i as u64) as usize);
+        }
+    }
+}
+
+/// A generated input with the answers to the parts it has one for.
+#[derive(Debug)]
+pub struct Generated {
+    pub input: String,
+    pub answers: Answers,
+}
+
+impl Generated {
+    // no generator is left to use it when every day is disabled
+    #[allow(dead_code)]
+    fn new(day: u8, lines: Vec<String>, part1: Option<String>, part2: Option<String>) -> Self {
+        let mut answers = Answers::default();
+        for (part, answer) in Part::BOTH.iter().zip(vec![part1, part2]) {
+            if let Some(answer) = answer {
+                answers.insert(day, *part, answer);
+            }
+        }
+        let mut input = lines.join("\n");
+        if !input.is_empty() {
+            input.push('\n');
+        }
+        Self { input, answers }
+    }
+}
+
+/// The share of valid entries when [`Options`] do not say otherwise.
+pub const DEFAULT_VALID_RATIO: f64 = 0.5;
+
+/// The days whose generator follows [`Options::valid_ratio`].
+pub const VALID_RATIO_DAYS: &[u8] = &[4];
+
+/// What a generated input looks like.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Options {
+    /// The number of entries of the input.
+    pub size: usize,
+    /// The share of entries that are valid, for the [`VALID_RATIO_DAYS`].
+    pub valid_ratio: f64,
+}
+
+impl Options {
+    pub fn new(size: usize) -> Self {
+        Self {
+            size,
+            valid_ratio: DEFAULT_VALID_RATIO,
+        }
+    }
+}
+
+/// The generator of a day.
+pub fn generator(day: u8) -> Option<fn(&mut Rng, Options) -> Generated> {
+    match day {
+        #[cfg(feature = "day1")]
+        1 => Some(|rng, options| day1(rng, options.size)),
+        #[cfg(feature = "day2")]
+        2 => Some(|rng, options| day2(rng, options.size)),
+        #[cfg(feature = "day3")]
+        3 => Some(|rng, options| day3(rng, options.size)),
+        #[cfg(feature = "day4")]
+        4 => Some(|rng, options| day4(rng, options.size, options.valid_ratio)),
+        #[cfg(feature = "day5")]
+        5 => Some(|rng, options| day5(rng, options.size)),
+        #[cfg(feature = "day6")]
+        6 => Some(|rng, options| day6(rng, options.size)),
+        #[cfg(feature = "day7")]
+        7 => Some(|rng, options| day7(rng, options.size)),
+        _ => None,
+    }
+}
+
+/// `size` numbers, at least five, among which only one pair and one triple sum to 2020, even
+/// reusing an entry. The other numbers are drawn below 2020 as well as above it, so that the
+/// searches can't rule them out by their value alone.
+#[cfg(feature = "day1")]
+pub fn day1(rng: &mut Rng, size: usize) -> Generated {
+    let planted = loop {
+        let a = rng.range(1..=2019) as i32;
+        let x = rng.range(1..=2018) as i32;
+        let y = rng.range(1..=(2019 - x) as u64) as i32;
+        let planted = [a, 2020 - a, x, y, 2020 - x - y];
+        if sums_to_2020(&planted) == (1, 1) {
+            break planted;
+        }
+    };
+
+    let mut sums = SumsBelow2020::default();
+    for n in planted.iter() {
+        sums.insert(*n);
+    }
+    let mut numbers = planted.to_vec();
+    while numbers.len() < size {
+        // below 2020, most numbers end up completing a sum once there are many of them
+        let below = (0..8)
+            .map(|_| rng.range(1..=2019) as i32)
+            .find(|n| !sums.completes(*n));
+        let filler = match below {
+            Some(n) if rng.chance(0.5) => n,
+            _ => rng.range(2021..=99_999) as i32,
+        };
+        sums.insert(filler);
+        numbers.push(filler);
+    }
+    rng.shuffle(&mut numbers);
+
+    let [a, b, x, y, z] = planted;
+    Generated::new(
+        1,
+        numbers.iter().map(i32::to_string).collect(),
+        Some((a * b).to_string()),
+        Some((x * y * z).to_string()),
+    )
+}
+
+/// How many pairs and triples of `numbers`, an entry allowed more than once, sum to 2020.
+#[cfg(feature = "day1")]
+fn sums_to_2020(numbers: &[i32]) -> (usize, usize) {
+    let (mut pairs, mut triples) = (0, 0);
+    for (i, a) in numbers.iter().enumerate() {
+        for (j, b) in numbers.iter().enumerate().skip(i) {
+            pairs += (a + b == 2020) as usize;
+            triples += numbers[j..].iter().filter(|c| a + b + *c == 2020).count();
+        }
+    }
+    (pairs, triples)
+}
+
+/// The positive numbers below 2020 of an input, and the sums up to 2020 of two of them, an
+/// entry allowed twice. Larger numbers never take part in a sum to 2020.
+#[cfg(feature = "day1")]
+struct SumsBelow2020 {
+    numbers: Vec<i32>,
+    present: Vec<bool>,
+    pairs: Vec<bool>,
+}
+
+#[cfg(feature = "day1")]
+impl Default for SumsBelow2020 {
+    fn default() -> Self {
+        Self {
+            numbers: vec![],
+            present: vec![false; 2021],
+            pairs: vec![false; 2021],
+        }
+    }
+}
+
+#[cfg(feature = "day1")]
+impl SumsBelow2020 {
+    /// Whether `n` would make a new pair or triple summing to 2020, even used twice.
+    fn completes(&self, n: i32) -> bool {
+        if !(1..2020).contains(&n) {
+            return false;
+        }
+        let rest = (2020 - n) as usize;
+        let n = n as usize;
+        self.present[rest] || self.pairs[rest] || rest == n || (rest > n && self.present[rest - n])
+    }
+
+    fn insert(&mut self, n: i32) {
+        if !(1..2020).contains(&n) || self.present[n as usize] {
+            return;
+        }
+        for m in self.numbers.iter().chain(Some(&n)) {
+            if let Some(sum) = self.pairs.get_mut((m + n) as usize) {
+                *sum = true;
+            }
+        }
+        self.present[n as usize] = true;
+        self.numbers.push(n);
+    }
+}
+
+/// `size` password policies over a few letters, so that about half of them hold.
+#[cfg(feature = "day2")]
+pub fn day2(rng: &mut Rng, size: usize) -> Generated {
+    let (mut part1, mut part2) = (0, 0);
+    let lines = (0..size)
+        .map(|_| {
+            let low = rng.range(1..=9) as usize;
+            let high = rng.range(low as u64..=low as u64 + 9) as usize;
+            let letter = (b'a' + rng.range(0..=4) as u8) as char;
+            let password: String = (0..rng.range(1..=20))
+                .map(|_| match rng.chance(0.4) {
+                    true => letter,
+                    false => (b'a' + rng.range(0..=9) as u8) as char,
+                })
+                .collect();
+
+            let count = password.chars().filter(|c| *c == letter).count();
+            part1 += (low..=high).contains(&count) as usize;
+            let at = |pos: usize| password.chars().nth(pos - 1) == Some(letter);
+            part2 += (at(low) != at(high)) as usize;
+
+            format!("{}-{} {}: {}", low, high, letter, password)
+        })
+        .collect();
+
+    Generated::new(2, lines, Some(part1.to_string()), Some(part2.to_string()))
+}
+
+/// A map of `size` rows, a quarter of its squares being trees. Part 2 has no answer when the
+/// product of the trees overflows.
+#[cfg(feature = "day3")]
+pub fn day3(rng: &mut Rng, size: usize) -> Generated {
+    const WIDTH: usize = 31;
+    let lines: Vec<String> = (0..size)
+        .map(|_| {
+            (0..WIDTH)
+                .map(|_| if rng.chance(0.25) { '#' } else { '.' })
+                .collect()
+        })
+        .collect();
+
+    let trees = |right: usize, down: usize| {
+        lines
+            .iter()
+            .step_by(down)
+            .enumerate()
+            .filter(|(i, row)| row.as_bytes()[i * right % WIDTH] == b'#')
+            .count() as i64
+    };
+    let part1 = trees(3, 1).to_string();
+    let part2 = [(3, 1), (5, 1), (7, 1), (1, 2)]
+        .iter()
+        .try_fold(trees(1, 1), |product, (right, down)| {
+            product.checked_mul(trees(*right, *down))
+        });
+
+    Generated::new(3, lines, Some(part1), part2.map(|p| p.to_string()))
+}
+
+#[cfg(feature = "day4")]
+const EYE_COLORS: [&str; 7] = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+#[cfg(feature = "day4")]
+const REQUIRED_FIELDS: [&str; 7] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"];
+
+/// A batch of `size` passports, `valid_ratio` of them valid. The others are split between
+/// passports missing a field and passports with an invalid value, which only part 1 accepts.
+#[cfg(feature = "day4")]
+pub fn day4(rng: &mut Rng, size: usize, valid_ratio: f64) -> Generated {
+    let (mut part1, mut part2) = (0, 0);
+    let lines = (0..size)
+        .map(|_| {
+            let mut fields: Vec<(&str, String)> = REQUIRED_FIELDS
+                .iter()
+                .map(|key| (*key, passport_value(rng, key, true)))
+                .collect();
+            if rng.chance(valid_ratio) {
+                part1 += 1;
+                part2 += 1;
+            } else if rng.chance(0.5) {
+                part1 += 1;
+                let (key, value) = &mut fields[rng.range(0..=6) as usize];
+                *value = passport_value(rng, key, false);
+            } else {
+                fields.remove(rng.range(0..=6) as usize);
+            }
+            if rng.chance(0.5) {
+                fields.push(("cid", rng.range(100..=350).to_string()));
+            }
+            rng.shuffle(&mut fields);
+
+            let mut passport = String::new();
+            for (i, (key, value)) in fields.iter().enumerate() {
+                if i > 0 {
+                    passport.push(if rng.chance(0.3) { '\n' } else { ' ' });
+                }
+                passport.push_str(&format!("{}:{}", key, value));
+            }
+            passport
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    Generated::new(
+        4,
+        vec![lines],
+        Some(part1.to_string()),
+        Some(part2.to_string()),
+    )
+}
+
+/// A value of a required passport field that the strict rules accept, or reject.
+#[cfg(feature = "day4")]
+fn passport_value(rng: &mut Rng, key: &str, valid: bool) -> String {
+    let year = |rng: &mut Rng, valid: RangeInclusive<u64>| {
+        let (start, end) = valid.into_inner();
+        match rng.chance(0.5) {
+            true => rng.range(start - 20..=start - 1),
+            false => rng.range(end + 1..=end + 20),
+        }
+    };
+    let hex = |rng: &mut Rng, len: usize| -> String {
+        (0..len)
+            .map(|_| *rng.pick(b"0123456789abcdef") as char)
+            .collect()
+    };
+
+    match (key, valid) {
+        ("byr", true) => rng.range(1920..=2002).to_string(),
+        ("byr", false) => year(rng, 1920..=2002).to_string(),
+        ("iyr", true) => rng.range(2010..=2020).to_string(),
+        ("iyr", false) => year(rng, 2010..=2020).to_string(),
+        ("eyr", true) => rng.range(2020..=2030).to_string(),
+        ("eyr", false) => year(rng, 2020..=2030).to_string(),
+        ("hgt", true) if rng.chance(0.5) => format!("{}cm", rng.range(150..=193)),
+        ("hgt", true) => format!("{}in", rng.range(59..=76)),
+        ("hgt", false) => match rng.range(0..=2) {
+            0 => format!("{}cm", rng.range(194..=250)),
+            1 => format!("{}in", rng.range(40..=58)),
+            _ => rng.range(150..=193).to_string(),
+        },
+        ("hcl", true) => format!("#{}", hex(rng, 6)),
+        ("hcl", false) if rng.chance(0.5) => hex(rng, 6),
+        ("hcl", false) => format!("#{}", hex(rng, 5)),
+        ("ecl", true) => rng.pick(&EYE_COLORS).to_string(),
+        ("ecl", false) => rng.pick(&["xry", "red", "zzz", "gmt"]).to_string(),
+        ("pid", true) => format!("{:09}", rng.range(0..=999_999_999)),
+        ("pid", false) if rng.chance(0.5) => format!("{:08}", rng.range(0..=99_999_999)),
+        ("pid", false) => format!("{:010}", rng.range(0..=9_999_999_999)),
+        _ => unreachable!("{} is not a required field", key),
+    }
+}
+
+/// `size` boarding passes of contiguous seats but one. The plane has 1024 seats, so there are
+/// at least 2 and at most 1023 passes whatever the `size`.
+#[cfg(feature = "day5")]
+pub fn day5(rng: &mut Rng, size: usize) -> Generated {
+    let size = size.clamp(2, 1023) as u64;
+    let first = rng.range(0..=1023 - size);
+    let missing = rng.range(first + 1..=first + size - 1);
+
+    let mut lines: Vec<String> = (first..=first + size)
+        .filter(|id| *id != missing)
+        .map(|id| {
+            let bits = |value: u64, len: u32, zero: char, one: char| -> String {
+                (0..len)
+                    .rev()
+                    .map(|bit| if value >> bit & 1 == 1 { one } else { zero })
+                    .collect()
+            };
+            bits(id / 8, 7, 'F', 'B') + &bits(id % 8, 3, 'L', 'R')
+        })
+        .collect();
+    rng.shuffle(&mut lines);
+
+    Generated::new(
+        5,
+        lines,
+        Some((first + size).to_string()),
+        Some(missing.to_string()),
+    )
+}
+
+/// `size` groups of up to five people, who share some answers within their group.
+#[cfg(feature = "day6")]
+pub fn day6(rng: &mut Rng, size: usize) -> Generated {
+    let (mut part1, mut part2) = (0, 0);
+    let answers = |rng: &mut Rng, p: f64| -> u32 {
+        (0..26).filter(|_| rng.chance(p)).fold(0, |m, l| m | 1 << l)
+    };
+    let groups = (0..size)
+        .map(|_| {
+            let shared = answers(rng, 0.2);
+            let people: Vec<u32> = (0..rng.range(1..=5))
+                .map(|_| match shared | answers(rng, 0.15) {
+                    0 => 1 << rng.range(0..=25),
+                    person => person,
+                })
+                .collect();
+            part1 += people.iter().fold(0, |m, p| m | p).count_ones();
+            part2 += people.iter().fold(!0, |m, p| m & p).count_ones();
+
+            people
+                .iter()
+                .map(|person| {
+                    let mut letters: Vec<char> = (0..26)
+                        .filter(|l| person >> l & 1 == 1)
+                        .map(|l| (b'a' + l as u8) as char)
+                        .collect();
+                    rng.shuffle(&mut letters);
+                    letters.into_iter().collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    Generated::new(
+        6,
+        vec![groups],
+        Some(part1.to_string()),
+        Some(part2.to_string()),
+    )
+}
+
+#[cfg(feature = "day7")]
+const ADJECTIVES: [&str; 16] = [
+    "light", "dark", "bright", "muted", "faded", "dotted", "vibrant", "dull", "pale", "wavy",
+    "clear", "dim", "drab", "mirrored", "plaid", "posh",
+];
+#[cfg(feature = "day7")]
+const COLORS: [&str; 16] = [
+    "red", "orange", "white", "yellow", "gold", "olive", "plum", "blue", "black", "green", "teal",
+    "violet", "maroon", "crimson", "silver", "beige",
+];
+
+/// The name of the `n`th bag that is not shiny gold: adjectives get numbered once every
+/// combination is taken.
+#[cfg(feature = "day7")]
+fn bag_color(n: usize) -> String {
+    let adjective = ADJECTIVES[n % ADJECTIVES.len()];
+    let color = COLORS[n / ADJECTIVES.len() % COLORS.len()];
+    match n / (ADJECTIVES.len() * COLORS.len()) {
+        0 => format!("{} {}", adjective, color),
+        round => format!("{}{} {}", adjective, round, color),
+    }
+}
+
+/// Rules for `size` bags, at least one, each holding up to three kinds of bags that come
+/// after it, so that no bag holds itself. At most eight bags come after shiny gold, which
+/// keeps the count of part 2 well within range.
+#[cfg(feature = "day7")]
+pub fn day7(rng: &mut Rng, size: usize) -> Generated {
+    let size = size.max(1);
+    let gold = size - 1 - (size - 1).min(8);
+    let name = |i: usize| match i {
+        _ if i == gold => "shiny gold".to_string(),
+        _ if i > gold => bag_color(i - 1),
+        _ => bag_color(i),
+    };
+
+    let contents: Vec<Vec<(i32, usize)>> = (0..size)
+        .map(|i| {
+            let kinds = rng.range(0..=3).min((size - 1 - i) as u64);
+            let mut inner = HashSet::new();
+            while (inner.len() as u64) < kinds {
+                inner.insert(rng.range(i as u64 + 1..=size as u64 - 1) as usize);
+            }
+            let mut inner: Vec<usize> = inner.into_iter().collect();
+            inner.sort_unstable();
+            inner
+                .into_iter()
+                .map(|j| (rng.range(1..=4) as i32, j))
+                .collect()
+        })
+        .collect();
+
+    // bags only hold the ones after them: the ones before shiny gold are settled walking
+    // backwards from it, the count of the ones after it walking back from the last one
+    let mut holds_gold = vec![false; size];
+    holds_gold[gold] = true;
+    for i in (0..gold).rev() {
+        holds_gold[i] = contents[i].iter().any(|(_, j)| holds_gold[*j]);
+    }
+    let mut counts: Vec<Option<i32>> = vec![Some(0); size];
+    for i in (gold..size).rev() {
+        counts[i] = contents[i].iter().try_fold(0i32, |total, (count, j)| {
+            total.checked_add(count.checked_mul(counts[*j]?.checked_add(1)?)?)
+        });
+    }
+
+    let mut lines: Vec<String> = contents
+        .iter()
+        .enumerate()
+        .map(|(i, inner)| {
+            let inner = match inner.is_empty() {
+                true => "no other bags".to_string(),
+                false => inner
+                    .iter()
+                    .map(|(count, j)| {
+                        let plural = if *count == 1 { "" } else { "s" };
+                        format!("{} {} bag{}", count, name(*j), plural)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            };
+            format!("{} bags contain {}.", name(i), inner)
+        })
+        .collect();
+    rng.shuffle(&mut lines);
+
+    Generated::new(
+        7,
+        lines,
+        Some((holds_gold.iter().filter(|h| **h).count() - 1).to_string()),
+        counts[gold].map(|c| c.to_string()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::find_day;
+
+    #[test]
+    fn splitmix64_sequence() {
+        let mut rng = Rng::new(0);
+        assert_eq!(0xe220_a839_7b1d_cdaf, rng.next_u64());
+        assert_eq!(0x6e78_9e6a_a1b9_65f4, rng.next_u64());
+
+        let mut rng = Rng::new(7);
+        for _ in 0..1000 {
+            assert!((3..=5).contains(&rng.range(3..=5)));
+        }
+        assert_eq!(1, rng.range(1..=1));
+    }
+
+    #[cfg(feature = "day7")]
+    #[test]
+    fn same_seed_same_input() {
+        let generate = |seed| day7(&mut Rng::new(seed), 50).input;
+
+        assert_eq!(generate(42), generate(42));
+        assert_ne!(generate(42), generate(43));
+    }
+
+    #[test]
+    fn solvers_find_the_generated_answers() {
+        // only the enabled days have a generator
+        for day in 1..=7 {
+            let generate = match generator(day) {
+                Some(generate) => generate,
+                None => continue,
+            };
+            for (seed, size) in [(1, 0), (2, 1), (3, 10), (4, 150)].iter() {
+                let generated = generate(&mut Rng::new(*seed), Options::new(*size));
+                for part in Part::BOTH.iter() {
+                    let outcome = find_day(day).unwrap().run(&generated.input, *part);
+                    let expected = generated.answers.get(day, *part);
+                    assert_eq!(
+                        expected,
+                        outcome.as_ref().ok().map(|o| o.answer.as_str()),
+                        "day {} part {}, seed {} size {}",
+                        day,
+                        part.number(),
+                        seed,
+                        size
+                    );
+                }
+            }
+        }
+        assert!(generator(8).is_none());
+    }
+
+    #[cfg(feature = "day1")]
+    #[test]
+    fn numbers_have_one_pair_and_one_triple() {
+        for seed in 0..5 {
+            let generated = day1(&mut Rng::new(seed), 300);
+            let numbers: Vec<i32> = generated
+                .input
+                .lines()
+                .map(|n| n.parse().unwrap())
+                .collect();
+
+            assert_eq!((1, 1), sums_to_2020(&numbers));
+            assert!(numbers.iter().filter(|n| **n < 2020).count() > 50);
+        }
+    }
+
+    #[cfg(feature = "day4")]
+    #[test]
+    fn passports_follow_the_valid_ratio() {
+        let options = Options {
+            size: 1000,
+            valid_ratio: 0.8,
+        };
+        let generated = generator(4).unwrap()(&mut Rng::new(5), options);
+        let valid: f64 = generated
+            .answers
+            .get(4, Part::Two)
+            .unwrap()
+            .parse()
+            .unwrap();
+
+        assert!((750.0..=850.0).contains(&valid));
+    }
+}
@@ -1,14 +1,54 @@
+use allocs::AllocStats;
+#[cfg(feature = "timing")]
+use bench::{Bench, Stats};
+use parse::Parse;
+use std::fmt::Display;
+use std::path::PathBuf;
+#[cfg(feature = "timing")]
+use std::time::Duration;
 use thiserror::Error;
+use trace::{Event, NoTrace, Trace};
 
+#[cfg(feature = "day1")]
 pub mod day1;
+#[cfg(feature = "day2")]
 pub mod day2;
+#[cfg(feature = "day3")]
 pub mod day3;
+#[cfg(feature = "day4")]
 pub mod day4;
+#[cfg(feature = "day5")]
 pub mod day5;
+#[cfg(feature = "day6")]
 pub mod day6;
+#[cfg(feature = "day7")]
 pub mod day7;
 
+pub mod allocs;
+pub mod answers;
+#[cfg(feature = "timing")]
+pub mod bench;
+#[cfg(feature = "io")]
+pub mod client;
+pub mod gen;
+#[cfg(feature = "timing")]
+pub mod output;
+pub mod parse;
+#[cfg(feature = "io")]
+pub mod scaffold;
+pub mod submit;
+#[cfg(feature = "io")]
+pub mod suite;
+pub mod trace;
 pub mod utils;
+#[cfg(feature = "io")]
+pub mod watch;
+
+#[cfg(test)]
+mod examples;
+// the helpers are used by the tests of modules behind `io`
+#[cfg(all(test, feature = "io"))]
+mod test_util;
 
 #[derive(Error, Debug)]
 pub enum AocError {
@@ -16,6 +56,357 @@ pub enum AocError {
     IOError(#[from] std::io::Error),
     #[error(transparent)]
     Utf8Error(#[from] std::str::Utf8Error),
+    #[error("no input found, tried: {}", .tried.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", "))]
+    InputNotFound { tried: Vec<PathBuf> },
+    #[error("day {day}, line {line}: {reason} in {text:?}")]
+    Parse {
+        day: u8,
+        line: usize,
+        text: String,
+        reason: String,
+    },
+    #[error("answers file, line {line}: invalid entry {text:?}")]
+    InvalidAnswer { line: usize, text: String },
+    #[error("submissions file, line {line}: invalid entry {text:?}")]
+    InvalidHistory { line: usize, text: String },
+    #[error("day {day} has no answer: {reason}")]
+    NoAnswer { day: u8, reason: String },
+    #[error("day {day}: {what} overflows")]
+    Overflow { day: u8, what: String },
+    #[error("solver panicked: {0}")]
+    Panic(String),
+    #[error("no session token, set AOC_SESSION{}", .0.as_ref().map(|f| format!(" or write it to {}", f.display())).unwrap_or_default())]
+    MissingSession(Option<PathBuf>),
+    #[error("request to {url} failed: {reason}")]
+    Http { url: String, reason: String },
+    #[error("not submitted: {0}")]
+    Refused(#[from] submit::Refusal),
+}
+
+impl AocError {
+    pub fn parse(day: u8, line: usize, text: &str, reason: impl ToString) -> Self {
+        AocError::Parse {
+            day,
+            line,
+            text: text.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn no_answer(day: u8, reason: impl ToString) -> Self {
+        AocError::NoAnswer {
+            day,
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn overflow(day: u8, what: impl ToString) -> Self {
+        AocError::Overflow {
+            day,
+            what: what.to_string(),
+        }
+    }
+
+    /// Moves the line of a parse error reported by a sub-parser down by `lines`,
+    /// so that it points into the whole input and not into the chunk it was given.
+    pub fn shifted(self, lines: usize) -> Self {
+        match self {
+            AocError::Parse {
+                day,
+                line,
+                text,
+                reason,
+            } => AocError::Parse {
+                day,
+                line: line + lines,
+                text,
+                reason,
+            },
+            other => other,
+        }
+    }
 }
 
 pub type AocResult<T> = Result<T, AocError>;
+
+/// A puzzle solver: parse the raw input once, then answer both parts from it.
+pub trait Solution {
+    const DAY: u8;
+
+    type Input<'a>: Parse<'a>;
+    type Part1: Display;
+    type Part2: Display;
+
+    /// The input borrows from the text it is parsed from, when it needs to.
+    fn parse(input: &str) -> AocResult<Self::Input<'_>> {
+        Parse::parse(input)
+    }
+
+    /// A part fails when the input, however well-formed, has no answer. The decisions taken
+    /// on the way are reported to `trace`.
+    fn part1(input: &Self::Input<'_>, trace: &mut impl Trace) -> AocResult<Self::Part1>;
+    fn part2(input: &Self::Input<'_>, trace: &mut impl Trace) -> AocResult<Self::Part2>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const BOTH: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+
+    pub fn from_number(number: u8) -> Option<Self> {
+        match number {
+            1 => Some(Part::One),
+            2 => Some(Part::Two),
+            _ => None,
+        }
+    }
+}
+
+/// The answer to a part, with the time spent parsing the input and solving the part when
+/// parts are timed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outcome {
+    pub answer: String,
+    #[cfg(feature = "timing")]
+    pub parse_time: Duration,
+    #[cfg(feature = "timing")]
+    pub solve_time: Duration,
+    /// What solving the part allocated, when allocations are being profiled.
+    pub allocs: Option<AllocStats>,
+}
+
+/// Parsing and solving statistics of a benchmarked part.
+#[cfg(feature = "timing")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchOutcome {
+    pub answer: String,
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+/// A part solved with [`Day::explain`]: its outcome, and the decisions that led to it even
+/// when it has no answer.
+#[derive(Debug)]
+pub struct Explanation {
+    pub outcome: AocResult<Outcome>,
+    pub events: Vec<Event>,
+}
+
+/// A type-erased entry of the registry, so that every day can be driven the same way.
+#[derive(Clone, Copy)]
+pub struct Day {
+    pub number: u8,
+    run: fn(&str, Part) -> AocResult<Outcome>,
+    #[cfg(feature = "timing")]
+    bench: fn(&str, Part, &Bench) -> AocResult<BenchOutcome>,
+    explain: fn(&str, Part) -> AocResult<Explanation>,
+}
+
+impl Day {
+    pub const fn new<S: Solution>() -> Self {
+        Self {
+            number: S::DAY,
+            run: run::<S>,
+            #[cfg(feature = "timing")]
+            bench: bench::<S>,
+            explain: explain::<S>,
+        }
+    }
+
+    /// Parses `input` and solves `part`, timing the two steps separately when parts are
+    /// timed.
+    pub fn run(&self, input: &str, part: Part) -> AocResult<Outcome> {
+        (self.run)(input, part)
+    }
+
+    /// Like [`Day::run`], but samples both steps according to `bench`.
+    #[cfg(feature = "timing")]
+    pub fn bench(&self, input: &str, part: Part, bench: &Bench) -> AocResult<BenchOutcome> {
+        (self.bench)(input, part, bench)
+    }
+
+    /// Like [`Day::run`], keeping what the solver decided. Only the input failing to parse
+    /// leaves nothing to explain.
+    pub fn explain(&self, input: &str, part: Part) -> AocResult<Explanation> {
+        (self.explain)(input, part)
+    }
+}
+
+fn solve<S: Solution>(
+    data: &S::Input<'_>,
+    part: Part,
+    trace: &mut impl Trace,
+) -> AocResult<String> {
+    Ok(match part {
+        Part::One => S::part1(data, trace)?.to_string(),
+        Part::Two => S::part2(data, trace)?.to_string(),
+    })
+}
+
+fn run<S: Solution>(input: &str, part: Part) -> AocResult<Outcome> {
+    #[cfg(feature = "timing")]
+    let (parse_time, data) = utils::measure(|| S::parse(input));
+    #[cfg(not(feature = "timing"))]
+    let data = S::parse(input);
+    let data = data?;
+    #[cfg(feature = "timing")]
+    let (solve_time, (allocs, answer)) =
+        utils::measure(|| allocs::measure(|| solve::<S>(&data, part, &mut NoTrace)));
+    #[cfg(not(feature = "timing"))]
+    let (allocs, answer) = allocs::measure(|| solve::<S>(&data, part, &mut NoTrace));
+    Ok(Outcome {
+        answer: answer?,
+        #[cfg(feature = "timing")]
+        parse_time,
+        #[cfg(feature = "timing")]
+        solve_time,
+        allocs,
+    })
+}
+
+#[cfg(feature = "timing")]
+fn bench<S: Solution>(input: &str, part: Part, bench: &Bench) -> AocResult<BenchOutcome> {
+    let data = S::parse(input)?;
+    let parse = bench.run(|| S::parse(input));
+    let answer = solve::<S>(&data, part, &mut NoTrace)?;
+    let solve = bench.run(|| solve::<S>(&data, part, &mut NoTrace));
+    Ok(BenchOutcome {
+        answer,
+        parse,
+        solve,
+    })
+}
+
+fn explain<S: Solution>(input: &str, part: Part) -> AocResult<Explanation> {
+    #[cfg(feature = "timing")]
+    let (parse_time, data) = utils::measure(|| S::parse(input));
+    #[cfg(not(feature = "timing"))]
+    let data = S::parse(input);
+    let data = data?;
+    let mut events = vec![];
+    #[cfg(feature = "timing")]
+    let (solve_time, answer) = utils::measure(|| solve::<S>(&data, part, &mut events));
+    #[cfg(not(feature = "timing"))]
+    let answer = solve::<S>(&data, part, &mut events);
+    Ok(Explanation {
+        outcome: answer.map(|answer| Outcome {
+            answer,
+            #[cfg(feature = "timing")]
+            parse_time,
+            #[cfg(feature = "timing")]
+            solve_time,
+            allocs: None,
+        }),
+        events,
+    })
+}
+
+pub const DAYS: &[Day] = &[
+    #[cfg(feature = "day1")]
+    Day::new::<day1::Day1>(),
+    #[cfg(feature = "day2")]
+    Day::new::<day2::Day2>(),
+    #[cfg(feature = "day3")]
+    Day::new::<day3::Day3>(),
+    #[cfg(feature = "day4")]
+    Day::new::<day4::Day4>(),
+    #[cfg(feature = "day5")]
+    Day::new::<day5::Day5>(),
+    #[cfg(feature = "day6")]
+    Day::new::<day6::Day6>(),
+    #[cfg(feature = "day7")]
+    Day::new::<day7::Day7>(),
+];
+
+pub fn find_day(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.number == number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_lists_days_by_number() {
+        // the days whose feature is enabled, sorted, from the build script
+        let enabled: Vec<u8> = env!("AOC_ENABLED_DAYS")
+            .split(',')
+            .filter(|day| !day.is_empty())
+            .map(|day| day.parse().unwrap())
+            .collect();
+
+        let numbers: Vec<u8> = DAYS.iter().map(|d| d.number).collect();
+        assert!(numbers.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(enabled, numbers);
+        for day in 0..=25 {
+            assert_eq!(enabled.contains(&day), find_day(day).is_some());
+        }
+    }
+
+    #[cfg(feature = "day6")]
+    #[test]
+    fn run_parses_and_solves_a_day() {
+        let outcome = find_day(6)
+            .unwrap()
+            .run("abc\n\na\nb\nc", Part::One)
+            .unwrap();
+        assert_eq!("6", outcome.answer);
+    }
+
+    #[cfg(all(feature = "day6", feature = "timing"))]
+    #[test]
+    fn bench_samples_parse_and_solve() {
+        let bench = Bench {
+            warmup: 1,
+            budget: bench::Budget::Iterations(4),
+        };
+        let outcome = find_day(6)
+            .unwrap()
+            .bench("abc\n\na\nb\nc", Part::Two, &bench)
+            .unwrap();
+
+        assert_eq!("3", outcome.answer);
+        assert_eq!(4, outcome.parse.samples);
+        assert_eq!(4, outcome.solve.samples);
+    }
+
+    #[cfg(feature = "day6")]
+    #[test]
+    fn explain_keeps_the_decisions() {
+        let explanation = find_day(6)
+            .unwrap()
+            .explain("abc\n\na\nb\nc", Part::One)
+            .unwrap();
+
+        assert_eq!("6", explanation.outcome.unwrap().answer);
+        assert_eq!(2, explanation.events.len());
+        assert_eq!("group 2", explanation.events[1].subject);
+    }
+
+    #[cfg(feature = "day1")]
+    #[test]
+    fn parse_error_names_day_line_and_text() {
+        let err = find_day(1).unwrap().run("1721\n979\nfoo", Part::One);
+        match err {
+            Err(AocError::Parse {
+                day, line, text, ..
+            }) => {
+                assert_eq!(1, day);
+                assert_eq!(3, line);
+                assert_eq!("foo", text);
+            }
+            _ => panic!("expected a parse error"),
+        }
+    }
+}
@@ -0,0 +1,385 @@
+use crate::allocs::AllocStats;
+use crate::bench::Stats;
+use crate::trace::Event;
+use crate::{Outcome, Part};
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Locale {
+    It,
+    En,
+}
+
+impl Locale {
+    fn day_part(self) -> &'static str {
+        match self {
+            Locale::It => "Giorno {day}, parte {part}",
+            Locale::En => "Day {day}, part {part}",
+        }
+    }
+
+    fn result(self) -> &'static str {
+        match self {
+            Locale::It => "Risultato: {answer} in {solve} (parsing {parse})",
+            Locale::En => "Result: {answer} in {solve} (parsing {parse})",
+        }
+    }
+
+    fn allocs(self) -> &'static str {
+        match self {
+            Locale::It => "Allocazioni: {allocations} ({bytes} byte, picco {peak} byte)",
+            Locale::En => "Allocations: {allocations} ({bytes} bytes, peak {peak} bytes)",
+        }
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "it" => Ok(Locale::It),
+            "en" => Ok(Locale::En),
+            _ => Err(format!("unknown locale {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// The banner, for humans.
+    Pretty(Locale),
+    /// Only the answer, one per line.
+    Plain,
+    /// One JSON object per line.
+    Json,
+    Csv,
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Format::Pretty(Locale::It)
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pretty" => Ok(Format::default()),
+            "plain" => Ok(Format::Plain),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("unknown format {}", s)),
+        }
+    }
+}
+
+/// One solved part, as reported to the user.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub day: u8,
+    pub part: Part,
+    pub answer: String,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+    pub allocs: Option<AllocStats>,
+}
+
+impl Record {
+    pub fn new(day: u8, part: Part, outcome: Outcome) -> Self {
+        Self {
+            day,
+            part,
+            answer: outcome.answer,
+            parse_time: outcome.parse_time,
+            solve_time: outcome.solve_time,
+            allocs: outcome.allocs,
+        }
+    }
+}
+
+const CSV_HEADER: &str = "day,part,answer,parse_time_ns,solve_time_ns";
+const CSV_ALLOCS_HEADER: &str = ",allocations,allocated_bytes,peak_bytes";
+
+/// Writes records in a [`Format`], taking care of headers.
+pub struct Output<W: Write> {
+    format: Format,
+    writer: W,
+    header_written: bool,
+}
+
+impl<W: Write> Output<W> {
+    pub fn new(format: Format, writer: W) -> Self {
+        Self {
+            format,
+            writer,
+            header_written: false,
+        }
+    }
+
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    pub fn record(&mut self, record: &Record) -> io::Result<()> {
+        if self.format == Format::Csv && !self.header_written {
+            // every record of a run is profiled or none is
+            match record.allocs {
+                Some(_) => writeln!(self.writer, "{}{}", CSV_HEADER, CSV_ALLOCS_HEADER)?,
+                None => writeln!(self.writer, "{}", CSV_HEADER)?,
+            }
+            self.header_written = true;
+        }
+        writeln!(self.writer, "{}", render(self.format, record))
+    }
+
+    /// Benchmark statistics only make sense in the pretty format, the other ones
+    /// carry the medians in their records.
+    pub fn bench_details(&mut self, parse: &Stats, solve: &Stats) -> io::Result<()> {
+        if let Format::Pretty(_) = self.format {
+            writeln!(self.writer, "  parse: {}", parse)?;
+            writeln!(self.writer, "  solve: {}", solve)?;
+        }
+        Ok(())
+    }
+
+    /// The decisions behind the answer to a part, one per line after its record. A CSV file
+    /// has no room for them.
+    pub fn explanation(&mut self, day: u8, part: Part, events: &[Event]) -> io::Result<()> {
+        for event in events {
+            match self.format {
+                Format::Json => writeln!(
+                    self.writer,
+                    r#"{{"day":{},"part":{},"subject":{},"decision":{}}}"#,
+                    day,
+                    part.number(),
+                    json_string(&event.subject),
+                    json_string(&event.decision)
+                )?,
+                Format::Csv => {}
+                _ => writeln!(self.writer, "  {}: {}", event.subject, event.decision)?,
+            }
+        }
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+pub fn render(format: Format, record: &Record) -> String {
+    match format {
+        Format::Pretty(locale) => {
+            let rule = "-".repeat(40);
+            let title = locale
+                .day_part()
+                .replace("{day}", &record.day.to_string())
+                .replace("{part}", &record.part.number().to_string());
+            let result = locale
+                .result()
+                .replace("{answer}", &record.answer)
+                .replace("{solve}", &format!("{:#?}", record.solve_time))
+                .replace("{parse}", &format!("{:#?}", record.parse_time));
+            match record.allocs {
+                Some(allocs) => {
+                    let allocs = locale
+                        .allocs()
+                        .replace("{allocations}", &allocs.allocations.to_string())
+                        .replace("{bytes}", &allocs.bytes.to_string())
+                        .replace("{peak}", &allocs.peak.to_string());
+                    format!("{}\n{}\n{}\n{}\n{}", rule, title, result, allocs, rule)
+                }
+                None => format!("{}\n{}\n{}\n{}", rule, title, result, rule),
+            }
+        }
+        Format::Plain => record.answer.clone(),
+        Format::Json => {
+            let allocs = record
+                .allocs
+                .map(|a| {
+                    format!(
+                        r#","allocations":{},"allocated_bytes":{},"peak_bytes":{}"#,
+                        a.allocations, a.bytes, a.peak
+                    )
+                })
+                .unwrap_or_default();
+            format!(
+                r#"{{"day":{},"part":{},"answer":{},"parse_time_ns":{},"solve_time_ns":{}{}}}"#,
+                record.day,
+                record.part.number(),
+                json_string(&record.answer),
+                record.parse_time.as_nanos(),
+                record.solve_time.as_nanos(),
+                allocs
+            )
+        }
+        Format::Csv => {
+            let allocs = record
+                .allocs
+                .map(|a| format!(",{},{},{}", a.allocations, a.bytes, a.peak))
+                .unwrap_or_default();
+            format!(
+                "{},{},{},{},{}{}",
+                record.day,
+                record.part.number(),
+                csv_field(&record.answer),
+                record.parse_time.as_nanos(),
+                record.solve_time.as_nanos(),
+                allocs
+            )
+        }
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(answer: &str) -> Record {
+        Record {
+            day: 3,
+            part: Part::Two,
+            answer: answer.to_string(),
+            parse_time: Duration::from_micros(12),
+            solve_time: Duration::from_millis(2),
+            allocs: None,
+        }
+    }
+
+    fn profiled(answer: &str) -> Record {
+        Record {
+            allocs: Some(AllocStats {
+                allocations: 3,
+                bytes: 120,
+                peak: 80,
+            }),
+            ..record(answer)
+        }
+    }
+
+    #[test]
+    fn render_pretty() {
+        let rule = "-".repeat(40);
+
+        assert_eq!(
+            format!(
+                "{}\nGiorno 3, parte 2\nRisultato: 336 in 2ms (parsing 12µs)\n{}",
+                rule, rule
+            ),
+            render(Format::Pretty(Locale::It), &record("336"))
+        );
+        assert_eq!(
+            format!(
+                "{}\nDay 3, part 2\nResult: 336 in 2ms (parsing 12µs)\n{}",
+                rule, rule
+            ),
+            render(Format::Pretty(Locale::En), &record("336"))
+        );
+    }
+
+    #[test]
+    fn render_pretty_with_allocs() {
+        let rule = "-".repeat(40);
+
+        assert_eq!(
+            format!(
+                "{}\nDay 3, part 2\nResult: 336 in 2ms (parsing 12µs)\nAllocations: 3 (120 bytes, peak 80 bytes)\n{}",
+                rule, rule
+            ),
+            render(Format::Pretty(Locale::En), &profiled("336"))
+        );
+    }
+
+    #[test]
+    fn render_plain() {
+        assert_eq!("336", render(Format::Plain, &record("336")));
+    }
+
+    #[test]
+    fn render_json() {
+        assert_eq!(
+            r#"{"day":3,"part":2,"answer":"a \"b\"","parse_time_ns":12000,"solve_time_ns":2000000}"#,
+            render(Format::Json, &record("a \"b\""))
+        );
+        assert_eq!(
+            r#"{"day":3,"part":2,"answer":"336","parse_time_ns":12000,"solve_time_ns":2000000,"allocations":3,"allocated_bytes":120,"peak_bytes":80}"#,
+            render(Format::Json, &profiled("336"))
+        );
+    }
+
+    #[test]
+    fn explanation_follows_the_format() {
+        let events = [Event::new("passport 2", "invalid hgt \"190in\"")];
+        let explain = |format| {
+            let mut output = Output::new(format, vec![]);
+            output.explanation(4, Part::Two, &events).unwrap();
+            String::from_utf8(output.into_inner()).unwrap()
+        };
+
+        assert_eq!(
+            "  passport 2: invalid hgt \"190in\"\n",
+            explain(Format::Plain)
+        );
+        assert_eq!(
+            r#"{"day":4,"part":2,"subject":"passport 2","decision":"invalid hgt \"190in\""}"#
+                .to_string()
+                + "\n",
+            explain(Format::Json)
+        );
+        assert_eq!("", explain(Format::Csv));
+    }
+
+    #[test]
+    fn csv_with_allocs() {
+        let mut output = Output::new(Format::Csv, vec![]);
+        output.record(&profiled("336")).unwrap();
+
+        assert_eq!(
+            "day,part,answer,parse_time_ns,solve_time_ns,allocations,allocated_bytes,peak_bytes\n3,2,336,12000,2000000,3,120,80\n",
+            String::from_utf8(output.into_inner()).unwrap()
+        );
+    }
+
+    #[test]
+    fn csv_has_a_single_header() {
+        let mut output = Output::new(Format::Csv, vec![]);
+        output.record(&record("336")).unwrap();
+        output.record(&record("1,2")).unwrap();
+
+        assert_eq!(
+            "day,part,answer,parse_time_ns,solve_time_ns\n3,2,336,12000,2000000\n3,2,\"1,2\",12000,2000000\n",
+            String::from_utf8(output.into_inner()).unwrap()
+        );
+    }
+}
@@ -0,0 +1,122 @@
+//! The one way inputs are parsed, whether the result owns its data or borrows it from the
+//! input text.
+
+use crate::{AocError, AocResult};
+use std::convert::TryFrom;
+
+/// A value parsed from an input that lives for `'a`.
+///
+/// Every `TryFrom<&'a str>` type failing with an [`AocError`] is a `Parse<'a>`, borrowed ones
+/// like `Positions<'a>` and owned ones alike. Owned types parsed with `FromStr` implement it by
+/// delegating to `str::parse`, which makes them `Parse<'a>` for any `'a`.
+pub trait Parse<'a>: Sized {
+    fn parse(input: &'a str) -> AocResult<Self>;
+}
+
+impl<'a, T: TryFrom<&'a str, Error = AocError>> Parse<'a> for T {
+    fn parse(input: &'a str) -> AocResult<Self> {
+        T::try_from(input)
+    }
+}
+
+/// The text of an input, kept by whoever loaded it so that any [`Parse`] type, owned or
+/// borrowing from it, can be parsed out of it for as long as it lives.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Loaded {
+    text: String,
+}
+
+impl Loaded {
+    pub fn new(text: String) -> Self {
+        Self { text }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn parse<'a, T: Parse<'a>>(&'a self) -> AocResult<T> {
+        T::parse(&self.text)
+    }
+}
+
+/// Two readings of the same input, for days whose parts disagree on its meaning.
+#[derive(Debug)]
+pub struct Both<A, B>(pub A, pub B);
+
+impl<'a, A: Parse<'a>, B: Parse<'a>> Parse<'a> for Both<A, B> {
+    fn parse(input: &'a str) -> AocResult<Self> {
+        Ok(Both(A::parse(input)?, B::parse(input)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[derive(Debug, PartialEq)]
+    struct Owned(Vec<u8>);
+
+    impl FromStr for Owned {
+        type Err = AocError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            s.lines()
+                .enumerate()
+                .map(|(i, n)| n.parse().map_err(|e| AocError::parse(0, i + 1, n, e)))
+                .collect::<AocResult<_>>()
+                .map(Owned)
+        }
+    }
+
+    impl Parse<'_> for Owned {
+        fn parse(input: &str) -> AocResult<Self> {
+            input.parse()
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Borrowed<'a>(Vec<&'a str>);
+
+    impl<'a> TryFrom<&'a str> for Borrowed<'a> {
+        type Error = AocError;
+
+        fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+            Ok(Borrowed(s.lines().collect()))
+        }
+    }
+
+    #[test]
+    fn parse_owned_and_borrowed() {
+        let input = "1\n2";
+
+        assert_eq!(Owned(vec![1, 2]), Parse::parse(input).unwrap());
+        assert_eq!(Borrowed(vec!["1", "2"]), Parse::parse(input).unwrap());
+
+        let both: Both<Owned, Borrowed> = Parse::parse(input).unwrap();
+        assert_eq!(Owned(vec![1, 2]), both.0);
+        assert_eq!(Borrowed(vec!["1", "2"]), both.1);
+    }
+
+    #[test]
+    fn loaded_text_lends_both_kinds() {
+        let loaded = Loaded::new("1\n2".to_string());
+        let borrowed: Borrowed = loaded.parse().unwrap();
+        let owned: Owned = loaded.parse().unwrap();
+
+        assert_eq!(Borrowed(vec!["1", "2"]), borrowed);
+        assert_eq!(Owned(vec![1, 2]), owned);
+        assert!(loaded.parse::<Both<Owned, Borrowed>>().is_ok());
+    }
+
+    #[test]
+    fn both_fails_with_either() {
+        let err = Both::<Borrowed, Owned>::parse("1\nx").unwrap_err();
+
+        assert_eq!(
+            "day 0, line 2: invalid digit found in string in \"x\"",
+            err.to_string()
+        );
+    }
+}
@@ -0,0 +1,316 @@
+//! Generates the files of a new day, following the layout of the existing ones.
+
+use crate::AocResult;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The module of a new day: a parse type, the `Solution` implementation, stubs for both
+/// parts and a test parsing the example.
+pub fn module(day: u8) -> String {
+    MODULE_TEMPLATE.replace("{day}", &day.to_string())
+}
+
+const MODULE_TEMPLATE: &str = r#"use crate::trace::Trace;
+use crate::{AocError, AocResult, Solution};
+use std::convert::TryFrom;
+
+#[derive(Debug)]
+pub struct Entries(pub Vec<String>);
+
+impl TryFrom<&str> for Entries {
+    type Error = AocError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.lines()
+            .enumerate()
+            .map(|(i, line)| match line.trim() {
+                "" => Err(AocError::parse(Day{day}::DAY, i + 1, line, "empty line")),
+                entry => Ok(entry.to_string()),
+            })
+            .collect::<AocResult<_>>()
+            .map(Self)
+    }
+}
+
+pub struct Day{day};
+
+impl Solution for Day{day} {
+    const DAY: u8 = {day};
+
+    type Input<'a> = Entries;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn part1(input: &Self::Input<'_>, _trace: &mut impl Trace) -> AocResult<Self::Part1> {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>, _trace: &mut impl Trace) -> AocResult<Self::Part2> {
+        part2(input)
+    }
+}
+
+// the stubs fail until the parts are solved, so that nothing is submitted or recorded
+pub fn part1(_entries: &Entries) -> AocResult<usize> {
+    // TODO: solve part 1
+    Err(AocError::no_answer(Day{day}::DAY, "not solved yet"))
+}
+
+pub fn part2(_entries: &Entries) -> AocResult<usize> {
+    // TODO: solve part 2
+    Err(AocError::no_answer(Day{day}::DAY, "not solved yet"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryInto;
+
+    #[test]
+    fn parse_example() {
+        let entries: Result<Entries, _> =
+            include_str!("../input/examples/day{day}_example.txt").try_into();
+        assert!(entries.is_ok());
+    }
+}
+"#;
+
+/// Adds the `pub mod` declaration and the registry entry of a day to `lib.rs`, both behind
+/// the feature of the day, keeping the lists in day order. A list that already has the day is
+/// left alone.
+pub fn register(lib: &str, day: u8) -> String {
+    let lines: Vec<&str> = lib.lines().collect();
+    let module_at = insert_position(&lines, day, |line| {
+        line.strip_prefix("pub mod day")?
+            .strip_suffix(';')?
+            .parse()
+            .ok()
+    });
+    let entry_at = insert_position(&lines, day, |line| {
+        line.trim()
+            .strip_prefix("Day::new::<day")?
+            .split("::")
+            .next()?
+            .parse()
+            .ok()
+    });
+
+    let mut result = String::new();
+    for i in 0..=lines.len() {
+        if module_at == Some(i) {
+            result.push_str(&format!(
+                "#[cfg(feature = \"day{0}\")]\npub mod day{0};\n",
+                day
+            ));
+        }
+        if entry_at == Some(i) {
+            result.push_str(&format!(
+                "    #[cfg(feature = \"day{0}\")]\n    Day::new::<day{0}::Day{0}>(),\n",
+                day
+            ));
+        }
+        if let Some(line) = lines.get(i) {
+            result.push_str(line);
+            result.push('\n');
+        }
+    }
+    result
+}
+
+/// The index of the line to insert `day` at, among the lines `number` recognizes as
+/// belonging to a day. A day goes before the attributes of the day following it.
+fn insert_position(lines: &[&str], day: u8, number: impl Fn(&str) -> Option<u8>) -> Option<usize> {
+    let days: Vec<(usize, u8)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| Some((i, number(line)?)))
+        .collect();
+    if days.iter().any(|(_, n)| *n == day) {
+        return None;
+    }
+    match days.iter().find(|(_, n)| *n > day) {
+        Some((mut i, _)) => {
+            while i > 0 && lines[i - 1].trim_start().starts_with("#[") {
+                i -= 1;
+            }
+            Some(i)
+        }
+        None => days.last().map(|(i, _)| i + 1),
+    }
+}
+
+/// Adds the feature of a day to `Cargo.toml`, enabled by default like the ones of the other
+/// days. A manifest that already has it is left alone.
+pub fn add_feature(manifest: &str, day: u8) -> String {
+    let feature = format!("day{}", day);
+    let day_number = |name: &str| {
+        name.trim_matches('"')
+            .strip_prefix("day")?
+            .parse::<u8>()
+            .ok()
+    };
+    let lines: Vec<&str> = manifest.lines().collect();
+    let feature_at = insert_position(&lines, day, |line| day_number(line.split(" =").next()?));
+
+    let mut result = String::new();
+    for i in 0..=lines.len() {
+        if feature_at == Some(i) {
+            result.push_str(&format!("{} = []\n", feature));
+        }
+        let line = match lines.get(i) {
+            Some(line) => *line,
+            None => break,
+        };
+        match line
+            .strip_prefix("default = [")
+            .and_then(|l| l.strip_suffix(']'))
+        {
+            Some(defaults) if feature_at.is_some() => {
+                let mut defaults: Vec<String> = defaults
+                    .split(',')
+                    .map(|d| d.trim().to_string())
+                    .filter(|d| !d.is_empty())
+                    .collect();
+                let at = defaults
+                    .iter()
+                    .position(|d| day_number(d).is_some_and(|n| n > day))
+                    .unwrap_or(defaults.len());
+                defaults.insert(at, format!("{:?}", feature));
+                result.push_str(&format!("default = [{}]", defaults.join(", ")));
+            }
+            _ => result.push_str(line),
+        }
+        result.push('\n');
+    }
+    result
+}
+
+/// Writes the files of a new day under the repository at `root`: the module, an empty input
+/// for `year` and an empty example with its answers, then registers the day in `lib.rs` and
+/// its feature in `Cargo.toml`.
+/// Returns the files created, or fails without touching anything if the module exists.
+pub fn create(root: &Path, year: u16, day: u8) -> AocResult<Vec<PathBuf>> {
+    let module_path = root.join("src").join(format!("day{}.rs", day));
+    if module_path.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", module_path.display()),
+        )
+        .into());
+    }
+
+    let lib_path = root.join("src").join("lib.rs");
+    let lib = fs::read_to_string(&lib_path)?;
+    let manifest_path = root.join("Cargo.toml");
+    let manifest = fs::read_to_string(&manifest_path)?;
+    let examples = root.join("input").join("examples");
+    let inputs = root.join("input").join(year.to_string());
+    fs::create_dir_all(&examples)?;
+    fs::create_dir_all(&inputs)?;
+
+    let answers = format!("# day{0} part1 <answer>\n# day{0} part2 <answer>\n", day);
+    let files = vec![
+        (module_path, module(day)),
+        (
+            examples.join(format!("day{}_example.txt", day)),
+            String::new(),
+        ),
+        (
+            examples.join(format!("day{}_example.answers", day)),
+            answers,
+        ),
+        (inputs.join(format!("day{}.txt", day)), String::new()),
+    ];
+    let mut created = vec![];
+    for (path, content) in files {
+        // an input fetched before the day was scaffolded is kept
+        if !path.exists() {
+            fs::write(&path, content)?;
+            created.push(path);
+        }
+    }
+    fs::write(&lib_path, register(&lib, day))?;
+    fs::write(&manifest_path, add_feature(&manifest, day))?;
+
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIB: &str = r#"#[cfg(feature = "day1")]
+pub mod day1;
+#[cfg(feature = "day3")]
+pub mod day3;
+
+pub mod utils;
+
+pub const DAYS: &[Day] = &[
+    #[cfg(feature = "day1")]
+    Day::new::<day1::Day1>(),
+    #[cfg(feature = "day3")]
+    Day::new::<day3::Day3>(),
+];
+"#;
+
+    #[test]
+    fn register_in_day_order() {
+        let lib = register(LIB, 2);
+
+        assert!(lib.contains(
+            "pub mod day1;\n#[cfg(feature = \"day2\")]\npub mod day2;\n#[cfg(feature = \"day3\")]\n"
+        ));
+        assert!(lib.contains(
+            "    Day::new::<day1::Day1>(),\n    #[cfg(feature = \"day2\")]\n    Day::new::<day2::Day2>(),\n    #[cfg(feature = \"day3\")]\n"
+        ));
+        assert!(register(LIB, 4).contains(
+            "pub mod day3;\n#[cfg(feature = \"day4\")]\npub mod day4;\n\npub mod utils;"
+        ));
+        assert!(register(LIB, 4)
+            .contains("    #[cfg(feature = \"day4\")]\n    Day::new::<day4::Day4>(),\n];"));
+        assert_eq!(LIB, register(LIB, 3));
+    }
+
+    #[test]
+    fn feature_in_day_order() {
+        let manifest = r#"[features]
+default = ["io", "day1", "day3"]
+io = []
+day1 = []
+day3 = ["regex"]
+
+[dependencies]
+"#;
+
+        assert_eq!(
+            r#"[features]
+default = ["io", "day1", "day2", "day3"]
+io = []
+day1 = []
+day2 = []
+day3 = ["regex"]
+
+[dependencies]
+"#,
+            add_feature(manifest, 2)
+        );
+        assert!(
+            add_feature(manifest, 4).contains("default = [\"io\", \"day1\", \"day3\", \"day4\"]\n")
+        );
+        assert!(add_feature(manifest, 4).contains("day3 = [\"regex\"]\nday4 = []\n\n"));
+        assert_eq!(manifest, add_feature(manifest, 3));
+    }
+
+    #[test]
+    fn module_is_filled_in() {
+        let module = module(12);
+
+        assert!(module.contains("pub struct Day12;"));
+        assert!(module.contains("const DAY: u8 = 12;"));
+        assert!(module.contains("include_str!(\"../input/examples/day12_example.txt\")"));
+        assert!(module.contains("Err(AocError::no_answer(Day12::DAY, \"not solved yet\"))"));
+        assert!(!module.contains("{day}"));
+    }
+}
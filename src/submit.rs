@@ -0,0 +1,438 @@
+use crate::parse::Parse;
+use crate::{AocError, AocResult, Part};
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+/// How long to wait after a wrong answer when the server did not say.
+pub const DEFAULT_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// What the server said about a guess.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verdict {
+    Right,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// The guess came too early and was not judged.
+    Wait(Duration),
+    /// The guess was for a part that is locked or already solved, and was not judged.
+    NotJudged,
+}
+
+impl Verdict {
+    /// Reads the verdict out of the page the server answers a submission with.
+    pub fn from_response(page: &str) -> Option<Self> {
+        if page.contains("That's the right answer") {
+            Some(Verdict::Right)
+        } else if page.contains("Did you already complete it") {
+            Some(Verdict::NotJudged)
+        } else if page.contains("You gave an answer too recently") {
+            Some(Verdict::Wait(parse_wait(page).unwrap_or(DEFAULT_COOLDOWN)))
+        } else if page.contains("your answer is too high") {
+            Some(Verdict::TooHigh)
+        } else if page.contains("your answer is too low") {
+            Some(Verdict::TooLow)
+        } else if page.contains("That's not the right answer") {
+            Some(Verdict::Wrong)
+        } else {
+            None
+        }
+    }
+}
+
+/// Parses the `You have 1m 30s left to wait` sentence of the server, `None` when it has no
+/// duration.
+fn parse_wait(page: &str) -> Option<Duration> {
+    let start = page.find("You have ")? + "You have ".len();
+    let end = start + page[start..].find(" left to wait")?;
+    let mut secs: Option<u64> = None;
+    for chunk in page[start..end].split_whitespace() {
+        let (value, unit) = match (chunk.strip_suffix('m'), chunk.strip_suffix('s')) {
+            (Some(minutes), _) => (minutes, 60),
+            (_, Some(seconds)) => (seconds, 1),
+            _ => return None,
+        };
+        let chunk = value.parse::<u64>().ok()?.checked_mul(unit)?;
+        secs = Some(secs.unwrap_or(0).checked_add(chunk)?);
+    }
+    secs.map(Duration::from_secs)
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Right => write!(f, "right"),
+            Verdict::Wrong => write!(f, "wrong"),
+            Verdict::TooHigh => write!(f, "too-high"),
+            Verdict::TooLow => write!(f, "too-low"),
+            Verdict::Wait(wait) => write!(f, "wait-{}", wait.as_secs()),
+            Verdict::NotJudged => write!(f, "not-judged"),
+        }
+    }
+}
+
+impl FromStr for Verdict {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "right" => Ok(Verdict::Right),
+            "wrong" => Ok(Verdict::Wrong),
+            "too-high" => Ok(Verdict::TooHigh),
+            "too-low" => Ok(Verdict::TooLow),
+            "not-judged" => Ok(Verdict::NotJudged),
+            s => s
+                .strip_prefix("wait-")
+                .and_then(|secs| secs.parse().ok())
+                .map(|secs| Verdict::Wait(Duration::from_secs(secs)))
+                .ok_or(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Guess {
+    pub day: u8,
+    pub part: Part,
+    /// Seconds since the unix epoch.
+    pub at: u64,
+    pub verdict: Verdict,
+    pub answer: String,
+}
+
+/// Why a guess is not worth sending.
+#[derive(Error, Debug, PartialEq)]
+pub enum Refusal {
+    #[error("{0:?} is empty or has whitespace in it, which no answer has")]
+    Malformed(String),
+    #[error("the part is already solved with {0}")]
+    AlreadySolved(String),
+    #[error("{0} has already been rejected")]
+    KnownWrong(String),
+    #[error("{answer} is not lower than {bound}, which is too high")]
+    TooHigh { answer: String, bound: i128 },
+    #[error("{answer} is not higher than {bound}, which is too low")]
+    TooLow { answer: String, bound: i128 },
+    #[error("wait {}s before the next guess", .0.as_secs())]
+    Cooldown(Duration),
+}
+
+/// Every guess ever submitted, one `day<n> part<n> <time> <verdict> <answer>` line each.
+#[derive(Debug, Default, PartialEq)]
+pub struct History(Vec<Guess>);
+
+impl History {
+    pub fn push(&mut self, guess: Guess) {
+        self.0.push(guess);
+    }
+
+    pub fn guesses(&self, day: u8, part: Part) -> impl Iterator<Item = &Guess> {
+        self.0
+            .iter()
+            .filter(move |g| g.day == day && g.part == part)
+    }
+
+    /// Checks a guess against what is already known, at `now` seconds since the epoch.
+    pub fn judge(
+        &self,
+        day: u8,
+        part: Part,
+        answer: &str,
+        now: u64,
+        cooldown: Duration,
+    ) -> Result<(), Refusal> {
+        // besides, the history keeps the answer last on a line of its own
+        if answer.is_empty() || answer.contains(char::is_whitespace) {
+            return Err(Refusal::Malformed(answer.to_string()));
+        }
+        let guesses: Vec<&Guess> = self.guesses(day, part).collect();
+
+        if let Some(right) = guesses.iter().find(|g| g.verdict == Verdict::Right) {
+            return Err(Refusal::AlreadySolved(right.answer.clone()));
+        }
+        let judged = |g: &&&Guess| !matches!(g.verdict, Verdict::Wait(_) | Verdict::NotJudged);
+        if guesses.iter().filter(judged).any(|g| g.answer == answer) {
+            return Err(Refusal::KnownWrong(answer.to_string()));
+        }
+
+        if let Ok(value) = answer.parse::<i128>() {
+            let bound = |verdict| {
+                guesses
+                    .iter()
+                    .filter(move |g| g.verdict == verdict)
+                    .filter_map(|g| g.answer.parse::<i128>().ok())
+            };
+            if let Some(high) = bound(Verdict::TooHigh).min().filter(|high| value >= *high) {
+                return Err(Refusal::TooHigh {
+                    answer: answer.to_string(),
+                    bound: high,
+                });
+            }
+            if let Some(low) = bound(Verdict::TooLow).max().filter(|low| value <= *low) {
+                return Err(Refusal::TooLow {
+                    answer: answer.to_string(),
+                    bound: low,
+                });
+            }
+        }
+
+        // the server cools down after any wrong guess, whatever the puzzle
+        let cooling = |g: &&Guess| !matches!(g.verdict, Verdict::Right | Verdict::NotJudged);
+        if let Some(last) = self.0.iter().rfind(cooling) {
+            let wait = match last.verdict {
+                Verdict::Wait(wait) => wait,
+                _ => cooldown,
+            };
+            let until = last.at + wait.as_secs();
+            if now < until {
+                return Err(Refusal::Cooldown(Duration::from_secs(until - now)));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+impl Parse<'_> for History {
+    fn parse(input: &str) -> AocResult<Self> {
+        input.parse()
+    }
+}
+
+impl FromStr for History {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut history = History::default();
+        for (i, line) in s.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let invalid = || AocError::InvalidHistory {
+                line: i + 1,
+                text: line.to_string(),
+            };
+
+            let mut fields = line.splitn(5, ' ');
+            let mut next = || fields.next().ok_or_else(invalid);
+            let day = next()?
+                .strip_prefix("day")
+                .and_then(|d| d.parse().ok())
+                .ok_or_else(invalid)?;
+            let part = next()?
+                .strip_prefix("part")
+                .and_then(|p| p.parse().ok())
+                .and_then(Part::from_number)
+                .ok_or_else(invalid)?;
+            let at = next()?.parse().map_err(|_| invalid())?;
+            let verdict = next()?.parse().map_err(|_| invalid())?;
+            let answer = next()?.to_string();
+
+            history.push(Guess {
+                day,
+                part,
+                at,
+                verdict,
+                answer,
+            });
+        }
+        Ok(history)
+    }
+}
+
+impl fmt::Display for History {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for g in &self.0 {
+            writeln!(
+                f,
+                "day{} part{} {} {} {}",
+                g.day,
+                g.part.number(),
+                g.at,
+                g.verdict,
+                g.answer
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guess(at: u64, verdict: Verdict, answer: &str) -> Guess {
+        Guess {
+            day: 1,
+            part: Part::One,
+            at,
+            verdict,
+            answer: answer.to_string(),
+        }
+    }
+
+    #[test]
+    fn parse_and_format() {
+        let s =
+            "day1 part1 100 too-high 500\nday1 part2 200 wait-35 12\nday1 part2 300 right 1 2\nday1 part1 400 not-judged 7\n";
+        let history: History = s.parse().unwrap();
+
+        assert_eq!(
+            Some(&guess(100, Verdict::TooHigh, "500")),
+            history.guesses(1, Part::One).next()
+        );
+        assert_eq!(s, history.to_string());
+        assert!("day1 part1 100 maybe 5".parse::<History>().is_err());
+    }
+
+    #[test]
+    fn refuse_known_answers_and_bounds() {
+        let mut history = History::default();
+        history.push(guess(0, Verdict::TooHigh, "500"));
+        history.push(guess(0, Verdict::TooLow, "100"));
+        history.push(guess(0, Verdict::Wrong, "abc"));
+        let judge = |answer| history.judge(1, Part::One, answer, 1000, DEFAULT_COOLDOWN);
+
+        assert_eq!(Err(Refusal::KnownWrong("abc".to_string())), judge("abc"));
+        assert_eq!(Err(Refusal::KnownWrong("500".to_string())), judge("500"));
+        assert_eq!(
+            Err(Refusal::TooHigh {
+                answer: "600".to_string(),
+                bound: 500
+            }),
+            judge("600")
+        );
+        assert_eq!(
+            Err(Refusal::TooLow {
+                answer: "99".to_string(),
+                bound: 100
+            }),
+            judge("99")
+        );
+        assert_eq!(Ok(()), judge("250"));
+        assert_eq!(Ok(()), judge("xyz"));
+    }
+
+    #[test]
+    fn refuse_answers_the_history_cannot_hold() {
+        let mut history = History::default();
+        for answer in &[
+            "",
+            "12\nday1 part1 0 right 12",
+            "12 ",
+            "1\t2",
+            " 12",
+            "12\r",
+        ] {
+            assert_eq!(
+                Err(Refusal::Malformed(answer.to_string())),
+                history.judge(1, Part::One, answer, 1000, DEFAULT_COOLDOWN)
+            );
+        }
+
+        let answer = "ABC-12_x";
+        assert_eq!(
+            Ok(()),
+            history.judge(1, Part::One, answer, 1000, DEFAULT_COOLDOWN)
+        );
+        history.push(guess(1000, Verdict::Wrong, answer));
+        assert_eq!(history, history.to_string().parse().unwrap());
+    }
+
+    #[test]
+    fn refuse_solved_parts() {
+        let mut history = History::default();
+        history.push(guess(0, Verdict::NotJudged, "41"));
+        assert_eq!(
+            Ok(()),
+            history.judge(1, Part::One, "41", 10, DEFAULT_COOLDOWN)
+        );
+        history.push(guess(0, Verdict::Right, "42"));
+
+        assert_eq!(
+            Err(Refusal::AlreadySolved("42".to_string())),
+            history.judge(1, Part::One, "43", 1000, DEFAULT_COOLDOWN)
+        );
+        assert_eq!(
+            Ok(()),
+            history.judge(1, Part::Two, "43", 1000, DEFAULT_COOLDOWN)
+        );
+    }
+
+    #[test]
+    fn honor_cooldown() {
+        let mut history = History::default();
+        history.push(guess(1000, Verdict::Wrong, "1"));
+
+        assert_eq!(
+            Err(Refusal::Cooldown(Duration::from_secs(20))),
+            history.judge(1, Part::One, "2", 1040, DEFAULT_COOLDOWN)
+        );
+        assert_eq!(
+            Ok(()),
+            history.judge(1, Part::One, "2", 1060, DEFAULT_COOLDOWN)
+        );
+
+        history.push(guess(1100, Verdict::Wait(Duration::from_secs(300)), "2"));
+        assert_eq!(
+            Err(Refusal::Cooldown(Duration::from_secs(100))),
+            history.judge(1, Part::Two, "2", 1300, DEFAULT_COOLDOWN)
+        );
+    }
+
+    #[test]
+    fn verdict_from_response() {
+        let page = |text| format!("<main><article><p>{}</p></article></main>", text);
+
+        assert_eq!(
+            Some(Verdict::Right),
+            Verdict::from_response(&page(
+                "That's the right answer! You are one gold star closer."
+            ))
+        );
+        assert_eq!(
+            Some(Verdict::TooLow),
+            Verdict::from_response(&page(
+                "That's not the right answer; your answer is too low."
+            ))
+        );
+        assert_eq!(
+            Some(Verdict::Wrong),
+            Verdict::from_response(&page("That's not the right answer."))
+        );
+        assert_eq!(
+            Some(Verdict::Wait(Duration::from_secs(95))),
+            Verdict::from_response(&page(
+                "You gave an answer too recently. You have 1m 35s left to wait."
+            ))
+        );
+        assert_eq!(
+            Some(Verdict::NotJudged),
+            Verdict::from_response(&page(
+                "You don't seem to be solving the right level.  Did you already complete it?"
+            ))
+        );
+        assert_eq!(
+            Some(Verdict::Wait(DEFAULT_COOLDOWN)),
+            Verdict::from_response(&page(
+                "You gave an answer too recently. You have  left to wait."
+            ))
+        );
+        assert_eq!(
+            Some(Verdict::Wait(DEFAULT_COOLDOWN)),
+            Verdict::from_response(&page(
+                "You gave an answer too recently. You have 1\u{e9} left to wait."
+            ))
+        );
+        assert_eq!(None, Verdict::from_response("<html></html>"));
+    }
+}
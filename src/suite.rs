@@ -0,0 +1,217 @@
+use crate::output::Record;
+use crate::parse::Loaded;
+use crate::utils::{measure, InputSource};
+use crate::{AocError, AocResult, Day, Part};
+use std::fmt;
+use std::io;
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+
+/// The records of a day, or what stopped it from being solved.
+#[derive(Debug)]
+pub struct DayReport {
+    pub day: u8,
+    pub result: AocResult<Vec<Record>>,
+}
+
+#[derive(Debug)]
+pub struct SuiteReport {
+    pub days: Vec<DayReport>,
+    /// From the first input being loaded to the last part being solved.
+    pub wall_clock: Duration,
+}
+
+impl SuiteReport {
+    pub fn records(&self) -> impl Iterator<Item = &Record> {
+        self.days
+            .iter()
+            .filter_map(|d| d.result.as_ref().ok())
+            .flatten()
+    }
+
+    pub fn failures(&self) -> usize {
+        self.days.iter().filter(|d| d.result.is_err()).count()
+    }
+}
+
+impl fmt::Display for SuiteReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>3}  {:>4}  {:<16}  {:>12}  {:>12}",
+            "day", "part", "answer", "parse", "solve"
+        )?;
+        for report in &self.days {
+            match &report.result {
+                Ok(records) => {
+                    for record in records {
+                        write!(
+                            f,
+                            "{:>3}  {:>4}  {:<16}  {:>12}  {:>12}",
+                            record.day,
+                            record.part.number(),
+                            record.answer,
+                            format!("{:.1?}", record.parse_time),
+                            format!("{:.1?}", record.solve_time)
+                        )?;
+                        match record.allocs {
+                            Some(a) => writeln!(
+                                f,
+                                "  {} allocs, {} B, peak {} B",
+                                a.allocations, a.bytes, a.peak
+                            )?,
+                            None => writeln!(f)?,
+                        }
+                    }
+                }
+                Err(err) => writeln!(f, "{:>3}  {:>4}  FAILED: {}", report.day, "-", err)?,
+            }
+        }
+        write!(
+            f,
+            "{} days, {} failed, total wall-clock {:.1?}",
+            self.days.len(),
+            self.failures(),
+            self.wall_clock
+        )
+    }
+}
+
+/// Loads the inputs of `days` concurrently and solves them in parallel on the worker threads
+/// of a tokio runtime. A day that fails does not stop the others.
+pub fn run_all(
+    days: &[Day],
+    parts: &[Part],
+    source: impl Fn(&Day) -> InputSource,
+) -> AocResult<SuiteReport> {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?;
+
+    let (wall_clock, days) = measure(|| {
+        runtime.block_on(async {
+            let handles: Vec<_> = days
+                .iter()
+                .map(|day| {
+                    let day = *day;
+                    let parts = parts.to_vec();
+                    let source = source(&day);
+                    (day.number, tokio::spawn(solve_day(day, parts, source)))
+                })
+                .collect();
+
+            let mut reports = vec![];
+            for (day, handle) in handles {
+                let result = match handle.await {
+                    Ok(result) => result,
+                    Err(err) => Err(AocError::Panic(err.to_string())),
+                };
+                reports.push(DayReport { day, result });
+            }
+            reports
+        })
+    });
+
+    Ok(SuiteReport { days, wall_clock })
+}
+
+/// Like [`load`](crate::utils::load), but through the reactor of the tokio runtime the suite
+/// runs on rather than the one of async-std.
+async fn load_input(source: &InputSource) -> AocResult<Loaded> {
+    if let InputSource::Stdin = source {
+        let mut contents = String::new();
+        tokio::io::stdin().read_to_string(&mut contents).await?;
+        return Ok(Loaded::new(contents));
+    }
+
+    let tried = source.candidates();
+    for path in &tried {
+        match tokio::fs::read(path).await {
+            Ok(contents) => {
+                let text = String::from_utf8(contents).map_err(|e| e.utf8_error())?;
+                return Ok(Loaded::new(text));
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Err(AocError::InputNotFound { tried })
+}
+
+async fn solve_day(day: Day, parts: Vec<Part>, source: InputSource) -> AocResult<Vec<Record>> {
+    let input = load_input(&source).await?;
+    let solved = tokio::task::spawn_blocking(move || {
+        parts
+            .iter()
+            .map(|part| {
+                Ok(Record::new(
+                    day.number,
+                    *part,
+                    day.run(input.text(), *part)?,
+                ))
+            })
+            .collect()
+    });
+
+    match solved.await {
+        Ok(records) => records,
+        Err(err) => Err(AocError::Panic(err.to_string())),
+    }
+}
+
+// the examples of days 2 and 3 are the ones checked
+#[cfg(all(test, feature = "day2", feature = "day3"))]
+mod tests {
+    use super::*;
+    use crate::DAYS;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    fn example_path(day: &Day) -> PathBuf {
+        let file = format!("input/examples/day{}_example.txt", day.number);
+        Path::new(env!("CARGO_MANIFEST_DIR")).join(file)
+    }
+
+    fn example(day: &Day) -> InputSource {
+        InputSource::Path(example_path(day))
+    }
+
+    /// The days with an example to run, leaving out the empty placeholder of a freshly
+    /// scaffolded day.
+    fn solved_days() -> Vec<Day> {
+        DAYS.iter()
+            .filter(|day| fs::metadata(example_path(day)).is_ok_and(|m| m.len() > 0))
+            .copied()
+            .collect()
+    }
+
+    #[test]
+    fn run_all_days_on_the_examples() {
+        let days = solved_days();
+        let report = run_all(&days, &[Part::One], example).unwrap();
+
+        assert_eq!(0, report.failures());
+        assert_eq!(days.len(), report.records().count());
+        let day3 = report.records().find(|r| r.day == 3).unwrap();
+        assert_eq!("7", day3.answer);
+    }
+
+    #[test]
+    fn failing_days_are_reported() {
+        let days = solved_days();
+        let report = run_all(&days, &Part::BOTH, |day| {
+            if day.number == 2 {
+                InputSource::from_arg("/nonexistent/day2.txt")
+            } else {
+                example(day)
+            }
+        })
+        .unwrap();
+
+        assert_eq!(1, report.failures());
+        assert_eq!((days.len() - 1) * 2, report.records().count());
+        assert!(report
+            .to_string()
+            .contains("  2     -  FAILED: no input found, tried: /nonexistent/day2.txt"));
+    }
+}
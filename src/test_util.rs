@@ -0,0 +1,12 @@
+//! Helpers shared by the tests of several modules.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// A `day1.txt` in a fresh temporary directory of its own, which is not created yet.
+pub fn temp_path(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("aoc2020-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir.join("day1.txt")
+}
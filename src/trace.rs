@@ -0,0 +1,67 @@
+//! What the solvers decide on the way to an answer, for when the answer is wrong.
+//!
+//! Solvers report their decisions to a [`Trace`]. The events are built lazily, so solving with
+//! [`NoTrace`] costs nothing more than solving without tracing at all.
+
+use std::fmt::Display;
+
+/// A decision taken about one piece of the input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    /// What the decision is about, e.g. `passport 3`.
+    pub subject: String,
+    /// What was decided, e.g. `invalid hgt "190in"`.
+    pub decision: String,
+}
+
+impl Event {
+    pub fn new(subject: impl Display, decision: impl Display) -> Self {
+        Self {
+            subject: subject.to_string(),
+            decision: decision.to_string(),
+        }
+    }
+}
+
+/// Where solvers report their decisions.
+pub trait Trace {
+    /// Records the event built by `event`, which is only called when the event is kept.
+    fn event(&mut self, event: impl FnOnce() -> Event);
+}
+
+/// Drops every event without building it.
+pub struct NoTrace;
+
+impl Trace for NoTrace {
+    #[inline(always)]
+    fn event(&mut self, _: impl FnOnce() -> Event) {}
+}
+
+/// Keeps every event, in the order they are reported.
+impl Trace for Vec<Event> {
+    fn event(&mut self, event: impl FnOnce() -> Event) {
+        self.push(event());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count_to(n: u32, trace: &mut impl Trace) -> u32 {
+        (1..=n).fold(0, |_, i| {
+            trace.event(|| Event::new("count", i));
+            i
+        })
+    }
+
+    #[test]
+    fn events_are_built_only_when_kept() {
+        NoTrace.event(|| panic!("dropped events are never built"));
+
+        let mut events = vec![];
+        assert_eq!(2, count_to(2, &mut events));
+        assert_eq!(vec![Event::new("count", 1), Event::new("count", 2)], events);
+        assert_eq!(3, count_to(3, &mut NoTrace));
+    }
+}
@@ -1,41 +1,168 @@
-use std::str::FromStr;
+#[cfg(feature = "io")]
+use async_std::{fs::File, io, prelude::*};
 
-use async_std::fs::File;
-use async_std::prelude::*;
-
-use crate::AocResult;
+#[cfg(feature = "io")]
+use crate::parse::Loaded;
+#[cfg(feature = "io")]
+use crate::{AocError, AocResult};
+use std::env;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "timing")]
 use std::time::{Duration, Instant};
 
-pub async fn read_file<T: FromStr>(file: &str) -> AocResult<T>
-where
-    <T as std::str::FromStr>::Err: std::fmt::Debug,
-{
-    let mut file = File::open(format!("input/2020/{}.txt", file)).await?;
-    let mut contents = Vec::new();
-    file.read_to_end(&mut contents).await?;
-    let data = std::str::from_utf8(&contents)?;
-    Ok(data.parse().expect("impossible to parse data"))
+pub const DEFAULT_YEAR: u16 = 2020;
+
+/// Directory that is searched for puzzle inputs before the ones of the repository.
+pub const INPUT_DIR_ENV: &str = "AOC_INPUT_DIR";
+
+/// Where the input of a puzzle comes from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputSource {
+    Stdin,
+    Path(PathBuf),
+    /// An input looked up by name, e.g. `day1`, in the input directories of a year.
+    Named {
+        year: u16,
+        name: String,
+    },
 }
 
-pub async fn read_file_contents(file: &str) -> String {
-    let mut file = File::open(format!("input/2020/{}.txt", file))
-        .await
-        .unwrap();
-    let mut contents = Vec::new();
-    file.read_to_end(&mut contents).await.unwrap();
-    let data = std::str::from_utf8(&contents).unwrap();
-    data.to_string()
+impl InputSource {
+    pub fn named(year: u16, name: &str) -> Self {
+        InputSource::Named {
+            year,
+            name: name.to_string(),
+        }
+    }
+
+    /// `-` reads from stdin, anything else is a path.
+    pub fn from_arg(arg: &str) -> Self {
+        match arg {
+            "-" => InputSource::Stdin,
+            path => InputSource::Path(path.into()),
+        }
+    }
+
+    /// The files that are tried, in order, to find this input.
+    pub fn candidates(&self) -> Vec<PathBuf> {
+        match self {
+            InputSource::Stdin => vec![],
+            InputSource::Path(path) => vec![path.clone()],
+            InputSource::Named { year, name } => {
+                let input_dir = env::var_os(INPUT_DIR_ENV).map(PathBuf::from);
+                named_candidates(input_dir.as_deref(), *year, name)
+            }
+        }
+    }
+
+    /// Where a downloaded copy of this input belongs: in [`INPUT_DIR_ENV`] if it is set,
+    /// otherwise in the inputs of the repository, which are always searched.
+    pub fn store_path(&self) -> Option<PathBuf> {
+        match self {
+            InputSource::Stdin => None,
+            InputSource::Path(path) => Some(path.clone()),
+            InputSource::Named { year, name } => {
+                let file = format!("{}.txt", name);
+                let dir = env::var_os(INPUT_DIR_ENV)
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("input"));
+                Some(dir.join(year.to_string()).join(file))
+            }
+        }
+    }
+}
+
+fn named_candidates(input_dir: Option<&Path>, year: u16, name: &str) -> Vec<PathBuf> {
+    let file = format!("{}.txt", name);
+    let mut candidates = vec![];
+    if let Some(dir) = input_dir {
+        candidates.push(dir.join(year.to_string()).join(&file));
+        candidates.push(dir.join(&file));
+    }
+    candidates.push(Path::new("input").join(year.to_string()).join(&file));
+    let repo_input = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("input")
+        .join(year.to_string())
+        .join(&file);
+    if !candidates.contains(&repo_input) {
+        candidates.push(repo_input);
+    }
+    candidates
+}
+
+/// Loads an input, to parse whatever the day needs out of it, owned or borrowed.
+#[cfg(feature = "io")]
+pub async fn load(source: &InputSource) -> AocResult<Loaded> {
+    read_input(source).await.map(Loaded::new)
 }
 
-pub fn print_result(res: impl ToString, duration: Duration) {
-    println!("----------------------------------------");
-    println!("Risultato: {} in {:#?}", res.to_string(), duration);
-    println!("----------------------------------------");
+#[cfg(feature = "io")]
+async fn read_input(source: &InputSource) -> AocResult<String> {
+    if let InputSource::Stdin = source {
+        let mut contents = String::new();
+        io::stdin().read_to_string(&mut contents).await?;
+        return Ok(contents);
+    }
+
+    let tried = source.candidates();
+    for path in &tried {
+        match read_path_contents(path).await {
+            Err(AocError::IOError(e)) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            result => return result,
+        }
+    }
+    Err(AocError::InputNotFound { tried })
 }
 
+#[cfg(feature = "io")]
+pub async fn read_path_contents(path: impl AsRef<Path>) -> AocResult<String> {
+    let mut file = File::open(path.as_ref()).await?;
+    let mut contents = Vec::new();
+    file.read_to_end(&mut contents).await?;
+    let data = std::str::from_utf8(&contents)?;
+    Ok(data.to_string())
+}
+
+/// How long `func` takes, by the clock of the standard library.
+#[cfg(feature = "timing")]
 pub fn measure<R>(func: impl FnOnce() -> R) -> (Duration, R) {
     let start = Instant::now();
     let result = func();
     let duration = start.elapsed();
     (duration, result)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn named_candidates_without_input_dir() {
+        let candidates = named_candidates(None, 2020, "day1");
+
+        assert_eq!(Path::new("input/2020/day1.txt"), candidates[0]);
+        assert!(candidates[1].ends_with("input/2020/day1.txt"));
+        assert!(candidates[1].is_absolute());
+    }
+
+    #[test]
+    fn named_candidates_with_input_dir() {
+        let candidates = named_candidates(Some(Path::new("/tmp/aoc")), 2019, "day3");
+
+        assert_eq!(Path::new("/tmp/aoc/2019/day3.txt"), candidates[0]);
+        assert_eq!(Path::new("/tmp/aoc/day3.txt"), candidates[1]);
+        assert_eq!(Path::new("input/2019/day3.txt"), candidates[2]);
+    }
+
+    #[cfg(feature = "io")]
+    #[test]
+    fn missing_input_names_every_path() {
+        let source = InputSource::from_arg("/nonexistent/day1.txt");
+        let err = async_std::task::block_on(load(&source)).unwrap_err();
+
+        assert_eq!(
+            "no input found, tried: /nonexistent/day1.txt",
+            err.to_string()
+        );
+    }
+}
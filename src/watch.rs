@@ -0,0 +1,251 @@
+//! Re-running a day whenever its input or its example fixtures change.
+
+use crate::answers::{Answers, Check};
+use crate::{AocResult, Day, Part};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// What tells a file apart from its previous version; `None` while it does not exist.
+type Stamp = Option<(SystemTime, u64)>;
+
+fn stamp(path: &Path) -> Stamp {
+    let meta = fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+/// The canonical form of `path`. A file that does not exist yet is resolved through its
+/// directory, or made absolute if that is missing too.
+fn canonical(path: PathBuf) -> PathBuf {
+    if let Ok(path) = fs::canonicalize(&path) {
+        return path;
+    }
+    let dir = match path.parent() {
+        Some(dir) if dir.as_os_str().is_empty() => Path::new("."),
+        Some(dir) => dir,
+        None => return path,
+    };
+    match (fs::canonicalize(dir), path.file_name()) {
+        (Ok(dir), Some(name)) => dir.join(name),
+        _ => std::path::absolute(&path).unwrap_or(path),
+    }
+}
+
+/// Polls a set of files for changes of their modification time or size, noticing files
+/// being created or removed too.
+pub struct Watcher {
+    files: Vec<(PathBuf, Stamp)>,
+}
+
+impl Watcher {
+    /// Watches each of `paths` once, however it is spelled: the same file given relative
+    /// and absolute is polled a single time.
+    pub fn new(paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        let mut files: Vec<(PathBuf, Stamp)> = vec![];
+        for path in paths.into_iter().map(canonical) {
+            if files.iter().all(|(p, _)| *p != path) {
+                let stamp = stamp(&path);
+                files.push((path, stamp));
+            }
+        }
+        Self { files }
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// The files that changed since the previous poll.
+    pub fn poll(&mut self) -> Vec<PathBuf> {
+        let mut changed = vec![];
+        for (path, last) in self.files.iter_mut() {
+            let current = stamp(path);
+            if current != *last {
+                *last = current;
+                changed.push(path.clone());
+            }
+        }
+        changed
+    }
+
+    /// Polls every `interval` until some file changes.
+    pub fn wait(&mut self, interval: Duration) -> Vec<PathBuf> {
+        loop {
+            let changed = self.poll();
+            if !changed.is_empty() {
+                return changed;
+            }
+            thread::sleep(interval);
+        }
+    }
+}
+
+/// The `day<n>_<name>.txt` fixtures of a day in `dir`, with their `.answers` files.
+pub fn examples(dir: &Path, day: u8) -> Vec<PathBuf> {
+    let prefix = format!("day{}_", day);
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|e| e.path())
+                .filter(|p| {
+                    let name = p.file_name().unwrap_or_default().to_string_lossy();
+                    name.starts_with(&prefix)
+                        && (name.ends_with(".txt") || name.ends_with(".answers"))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+/// Runs a day on an example fixture, checking every part its `.answers` file knows about.
+/// Fixtures without answers, or still empty, are checked against nothing.
+pub fn check_example(day: &Day, fixture: &Path) -> AocResult<Vec<(Part, String, Check)>> {
+    let input = fs::read_to_string(fixture)?;
+    let answers_path = fixture.with_extension("answers");
+    let answers: Answers = match answers_path.exists() && !input.is_empty() {
+        true => fs::read_to_string(answers_path)?.parse()?,
+        false => Answers::default(),
+    };
+
+    let mut checked = vec![];
+    for part in Part::BOTH.iter() {
+        if answers.get(day.number, *part).is_some() {
+            let answer = day.run(&input, *part)?.answer;
+            let check = answers.check(day.number, *part, &answer);
+            checked.push((*part, answer, check));
+        }
+    }
+    Ok(checked)
+}
+
+/// A timing followed by how it moved since the previous run, e.g. `1.2ms (-300.0µs, -20.0%)`.
+pub fn delta(previous: Option<Duration>, current: Duration) -> String {
+    let previous = match previous {
+        Some(previous) => previous,
+        None => return format!("{:.1?}", current),
+    };
+    let (sign, diff) = match current.checked_sub(previous) {
+        Some(diff) => ('+', diff),
+        None => ('-', previous - current),
+    };
+    let percent = match previous.as_nanos() {
+        0 => String::new(),
+        nanos => format!(
+            ", {}{:.1}%",
+            sign,
+            diff.as_nanos() as f64 * 100.0 / nanos as f64
+        ),
+    };
+    format!("{:.1?} ({}{:.1?}{})", current, sign, diff, percent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_path;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = temp_path(name).parent().unwrap().to_path_buf();
+        fs::create_dir_all(&dir).unwrap();
+        // the watcher reports canonical paths, the temp directory may be behind a symlink
+        fs::canonicalize(dir).unwrap()
+    }
+
+    #[test]
+    fn watcher_notices_changes() {
+        let dir = temp_dir("watch");
+        let input = dir.join("day1.txt");
+        let later = dir.join("day1_example.txt");
+        fs::write(&input, "1721\n").unwrap();
+        let mut watcher = Watcher::new(vec![input.clone(), later.clone()]);
+
+        assert!(watcher.poll().is_empty());
+        fs::write(&input, "1721\n979\n").unwrap();
+        assert_eq!(vec![input.clone()], watcher.poll());
+        assert!(watcher.poll().is_empty());
+        fs::write(&later, "1721\n").unwrap();
+        fs::remove_file(&input).unwrap();
+        assert_eq!(vec![input, later], watcher.poll());
+    }
+
+    #[test]
+    fn watcher_polls_a_file_once() {
+        let dir = temp_dir("watch-once");
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        let input = dir.join("day1.txt");
+        let later = dir.join("day1_example.txt");
+        fs::write(&input, "1721\n").unwrap();
+        let mut watcher = Watcher::new(vec![
+            input.clone(),
+            dir.join("nested/../day1.txt"),
+            later.clone(),
+            dir.join("nested/../day1_example.txt"),
+        ]);
+
+        assert_eq!(2, watcher.len());
+        fs::write(&input, "1721\n979\n").unwrap();
+        fs::write(&later, "1721\n").unwrap();
+        assert_eq!(2, watcher.poll().len());
+    }
+
+    #[test]
+    fn examples_of_a_day() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("input/examples");
+        let names: Vec<String> = examples(&dir, 5)
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+
+        assert_eq!(vec!["day5_example.answers", "day5_example.txt"], names);
+    }
+
+    #[cfg(feature = "day6")]
+    #[test]
+    fn check_example_answers() {
+        let dir = temp_dir("watch-example");
+        let fixture = dir.join("day6_example.txt");
+        fs::write(&fixture, "abc\n\na\nb\nc").unwrap();
+        fs::write(
+            fixture.with_extension("answers"),
+            "day6 part1 6\nday6 part2 4\n",
+        )
+        .unwrap();
+
+        let checked = check_example(crate::find_day(6).unwrap(), &fixture).unwrap();
+
+        assert_eq!(
+            vec![
+                (Part::One, "6".to_string(), Check::Pass),
+                (
+                    Part::Two,
+                    "3".to_string(),
+                    Check::Fail {
+                        expected: "4".to_string()
+                    }
+                ),
+            ],
+            checked
+        );
+    }
+
+    #[test]
+    fn timing_deltas() {
+        let ms = Duration::from_millis;
+
+        assert_eq!("2.0ms", delta(None, ms(2)));
+        assert_eq!(
+            "1.5ms (-500.0µs, -25.0%)",
+            delta(Some(ms(2)), ms(1) + ms(1) / 2)
+        );
+        assert_eq!("3.0ms (+1.0ms, +50.0%)", delta(Some(ms(2)), ms(3)));
+        assert_eq!("1.0ms (+1.0ms)", delta(Some(ms(0)), ms(1)));
+    }
+}